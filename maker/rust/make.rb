@@ -65,6 +65,7 @@ def execute_responses(path)
   m = /responses\/(.+)\.yaml/.match(path)
   name = m[1]
   yml = YAML.load_file(path).deep_symbolize_keys
+  @paging = nil
   properties = yml[:response][:properties]
   independence_flag = true
   res, refs, map = make_response(name, properties, independence_flag)
@@ -102,10 +103,12 @@ def execute_apis(path)
 
   bodies = []
   make_body(yml[:body], bodies) if yml[:body].present?
+  ranges = (yml.dig(:body, :properties) || []).filter{|it| it[:min].present? && it[:max].present?}
   api_struct = ERB.new(File.read("api_struct.erb")).result(binding)
   api_new = ERB.new(File.read("api_new.erb")).result(binding).gsub(/^/, "    ")
   setter = ERB.new(File.read("setter.erb")).result(binding).gsub(/^/, "    ")
   parameters = ERB.new(File.read("parameters.erb")).result(binding).gsub(/^/, "        ")
+  @paging = yml.dig(:response, :paging)
   responses, responses_refs = response_list(yml)
 
   erb = ERB.new(File.read("api.erb"))
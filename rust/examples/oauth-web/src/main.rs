@@ -61,5 +61,6 @@ async fn oauth(uri: Uri, cookies: Cookies) -> impl IntoResponse {
         .execute(&res.access_token)
         .await
         .unwrap();
-    Json(me).into_response()
+    println!("log_id={:?} elapsed={:?}", me.log_id, me.elapsed);
+    Json(me.into_inner()).into_response()
 }
@@ -2,33 +2,282 @@ pub mod get_v2_user_info;
 pub mod post_v2_video_list;
 pub mod post_v2_video_query;
 
-use crate::error::Error;
-use reqwest::RequestBuilder;
+use crate::{
+    error::Error,
+    options::{make_transport, TiktokOptions},
+    quota::{count_records, ResearchQuota},
+    telemetry::Telemetry,
+    transport::{HttpRequest, HttpResponse, Transport},
+};
+use http::{header::AUTHORIZATION, HeaderMap, StatusCode};
 use serde::de::DeserializeOwned;
+use std::{
+    future::Future,
+    ops::Deref,
+    time::{Duration, Instant},
+};
 
-pub async fn execute_api<T>(builder: RequestBuilder) -> Result<T, Error>
+// The parsed body together with the HTTP metadata of the response. `elapsed`
+// covers the request that produced the response, not earlier retries.
+#[derive(Debug, Clone)]
+pub struct ApiResponse<T> {
+    pub response: T,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub log_id: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn into_inner(self) -> T {
+        self.response
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|it| it.to_str().ok())
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ApiResponse<U> {
+        ApiResponse {
+            response: f(self.response),
+            status: self.status,
+            headers: self.headers,
+            log_id: self.log_id,
+            elapsed: self.elapsed,
+        }
+    }
+}
+
+impl<T> Deref for ApiResponse<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.response
+    }
+}
+
+pub async fn execute_api<T>(
+    request: HttpRequest,
+    options: &Option<TiktokOptions>,
+) -> Result<ApiResponse<T>, Error>
 where
     T: DeserializeOwned,
 {
-    let response = builder.send().await?;
-    let status_code = response.status();
+    let telemetry = Telemetry::new(&request, options);
+    let transport = make_transport(options);
+    let policy = options.as_ref().and_then(|it| it.retry.as_ref());
+    let started = Instant::now();
+    let deadline = policy.and_then(|it| it.deadline).map(|it| started + it);
+    let mut attempt = 0;
+    let res = telemetry
+        .instrument(async {
+            loop {
+                attempt += 1;
+                let res = execute_once(
+                    transport.as_ref(),
+                    request.clone(),
+                    options,
+                    &telemetry,
+                    deadline,
+                )
+                .await;
+                match res {
+                    Err(err) => {
+                        match policy.and_then(|it| it.retry_after(attempt, started.elapsed(), &err))
+                        {
+                            Some(backoff) => tokio::time::sleep(backoff).await,
+                            None => break Err(err),
+                        }
+                    }
+                    res => break res,
+                }
+            }
+        })
+        .await;
+    telemetry.finish(attempt - 1, &res);
+    res
+}
 
-    if status_code.is_success() {
-        Ok(response.json::<T>().await?)
-    } else {
-        let text = match response.text().await {
-            Ok(text) => text,
-            Err(err) => return Err(Error::Other(format!("{:?}", err), status_code)),
-        };
+async fn execute_once<T>(
+    transport: &dyn Transport,
+    request: HttpRequest,
+    options: &Option<TiktokOptions>,
+    telemetry: &Telemetry,
+    deadline: Option<Instant>,
+) -> Result<ApiResponse<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let mut request = request;
+    let research_quota = before_send(&mut request, options, deadline, tokio::time::sleep).await?;
+    let started = Instant::now();
+    let response = transport.send(request).await?;
+    after_send(response, started.elapsed(), research_quota, telemetry).await
+}
 
-        let json = match serde_json::from_str::<serde_json::Value>(&text) {
-            Ok(json) => json,
-            Err(_err) => return Err(Error::Other(text, status_code)),
-        };
+// Rate limiting, the retry deadline and the research quota reservation. Shared
+// with the blocking calls, which pass a sleep that blocks the thread.
+pub(crate) async fn before_send<'a, F>(
+    request: &mut HttpRequest,
+    options: &'a Option<TiktokOptions>,
+    deadline: Option<Instant>,
+    sleep: impl Fn(Duration) -> F,
+) -> Result<Option<&'a ResearchQuota>, Error>
+where
+    F: Future<Output = ()>,
+{
+    let path = request.path();
+    if let Some(rate_limiter) = options.as_ref().and_then(|it| it.rate_limiter.as_ref()) {
+        while let Some(wait) = rate_limiter.try_acquire(&path, bearer_code(request)) {
+            check_deadline(deadline, wait)?;
+            sleep(wait).await;
+        }
+    }
+    cap_timeout(request, deadline)?;
+    let research_quota = research_quota(options, &path);
+    if let Some(research_quota) = research_quota {
+        research_quota.reserve().await?;
+    }
+    Ok(research_quota)
+}
+
+// Telemetry, research quota records and parsing of a response. Shared with the
+// blocking calls.
+pub(crate) async fn after_send<T>(
+    response: HttpResponse,
+    elapsed: Duration,
+    research_quota: Option<&ResearchQuota>,
+    telemetry: &Telemetry,
+) -> Result<ApiResponse<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let json = parse_body(&response);
+    telemetry.response(&response, json.as_ref());
+    // TikTok already answered, so a store failure must not lose the response.
+    if let Some(research_quota) = research_quota {
+        let records = quota_records(&response, json.as_ref());
+        if let Err(err) = research_quota.complete(records).await {
+            telemetry.quota_failed(&err);
+        }
+    }
+    let log_id = response.header_log_id();
+    parse_response(response, json, elapsed).map_err(|err| err.with_log_id(log_id))
+}
 
-        match serde_json::from_value::<crate::responses::error::Error>(json["error"].clone()) {
+pub(crate) fn check_range<T>(
+    name: &str,
+    value: impl Into<Option<T>>,
+    min: T,
+    max: T,
+) -> Result<(), Error>
+where
+    T: PartialOrd + std::fmt::Display,
+{
+    match value.into() {
+        Some(value) if value < min || value > max => Err(Error::Validation(format!(
+            "{} must be between {} and {}, got {}",
+            name, min, max, value
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// Fails when waiting would run past the retry deadline.
+fn check_deadline(deadline: Option<Instant>, wait: Duration) -> Result<(), Error> {
+    match deadline {
+        Some(deadline) if Instant::now() + wait >= deadline => {
+            Err(Error::Timeout("retry deadline exceeded".into()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Caps the request timeout at the time left before the retry deadline.
+fn cap_timeout(request: &mut HttpRequest, deadline: Option<Instant>) -> Result<(), Error> {
+    let Some(deadline) = deadline else {
+        return Ok(());
+    };
+    let left = deadline.saturating_duration_since(Instant::now());
+    if left.is_zero() {
+        return Err(Error::Timeout("retry deadline exceeded".into()));
+    }
+    request.timeout = Some(request.timeout.map_or(left, |it| it.min(left)));
+    Ok(())
+}
+
+fn bearer_code(request: &HttpRequest) -> &str {
+    request
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|it| it.to_str().ok())
+        .unwrap_or_default()
+}
+
+fn research_quota<'a>(options: &'a Option<TiktokOptions>, path: &str) -> Option<&'a ResearchQuota> {
+    options
+        .as_ref()
+        .and_then(|it| it.research_quota.as_ref())
+        .filter(|_| ResearchQuota::is_research_path(path))
+}
+
+// The body is parsed once and shared by telemetry, the research quota and
+// the response type.
+fn parse_body(response: &HttpResponse) -> Option<serde_json::Value> {
+    serde_json::from_slice(&response.body).ok()
+}
+
+fn quota_records(response: &HttpResponse, json: Option<&serde_json::Value>) -> u64 {
+    match json {
+        Some(json) if response.status.is_success() => count_records(json),
+        _ => 0,
+    }
+}
+
+fn parse_response<T>(
+    response: HttpResponse,
+    json: Option<serde_json::Value>,
+    elapsed: Duration,
+) -> Result<ApiResponse<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let status_code = response.status;
+
+    let Some(mut json) = json else {
+        if status_code.is_success() {
+            // Report why the body is not JSON.
+            serde_json::from_slice::<serde_json::Value>(&response.body)?;
+        }
+        return Err(Error::Other(response.text(), status_code, None));
+    };
+    if status_code.is_success() {
+        // TikTok often answers 200 with the failure in error.code.
+        if let Some(err) = embedded_error(&response, &json) {
+            return Err(err);
+        }
+        Ok(ApiResponse {
+            log_id: response.log_id_in(Some(&json)),
+            response: serde_json::from_value::<T>(json)?,
+            status: status_code,
+            headers: response.headers,
+            elapsed,
+        })
+    } else {
+        match serde_json::from_value::<crate::responses::error::Error>(json["error"].take()) {
             Ok(err) => Err(Error::Api(err, status_code)),
-            Err(_) => Err(Error::Other(text, status_code)),
+            Err(_) => Err(Error::Other(response.text(), status_code, None)),
         }
     }
 }
+
+fn embedded_error(response: &HttpResponse, json: &serde_json::Value) -> Option<Error> {
+    let code = json["error"]["code"].as_str()?;
+    if code.is_empty() || code == "ok" {
+        return None;
+    }
+    match serde_json::from_value::<crate::responses::error::Error>(json["error"].clone()) {
+        Ok(err) => Some(Error::Api(err, response.status)),
+        Err(_) => Some(Error::Other(response.text(), response.status, None)),
+    }
+}
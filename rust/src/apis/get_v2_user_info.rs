@@ -1,12 +1,12 @@
 use crate::responses::user::UserField;
 use crate::responses::{error::Error, user::User};
 use crate::{
-    apis::execute_api,
+    apis::{execute_api, ApiResponse},
     error::Error as ApiError,
     options::{apply_options, make_url, TiktokOptions},
+    transport::HttpRequest,
 };
 use itertools::Itertools;
-use reqwest::RequestBuilder;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
@@ -24,18 +24,24 @@ impl Api {
     }
 
     #[allow(clippy::vec_init_then_push)]
-    pub fn build(self, bearer_code: &str) -> RequestBuilder {
+    pub fn build(self, bearer_code: &str) -> Result<HttpRequest, ApiError> {
         let mut query_parameters = vec![];
         query_parameters.push(("fields", self.fields.iter().join(",")));
-        let client = reqwest::Client::new()
-            .get(make_url(URL, &self.options))
+        let request = HttpRequest::get(&make_url(URL, &self.options))
             .query(&query_parameters)
-            .bearer_auth(bearer_code);
-        apply_options(client, &self.options)
+            .bearer_auth(bearer_code)?;
+        Ok(apply_options(request, &self.options))
     }
 
-    pub async fn execute(self, bearer_code: &str) -> Result<Response, ApiError> {
-        execute_api(self.build(bearer_code)).await
+    pub async fn execute(self, bearer_code: &str) -> Result<ApiResponse<Response>, ApiError> {
+        let options = self.options.clone();
+        execute_api(self.build(bearer_code)?, &options).await
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn execute_blocking(self, bearer_code: &str) -> Result<ApiResponse<Response>, ApiError> {
+        let options = self.options.clone();
+        crate::blocking::execute_api(self.build(bearer_code)?, &options)
     }
 }
 
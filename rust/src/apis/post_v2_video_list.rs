@@ -1,12 +1,12 @@
 use crate::responses::video::VideoField;
 use crate::responses::{error::Error, video::Video};
 use crate::{
-    apis::execute_api,
+    apis::{check_range, execute_api, ApiResponse},
     error::Error as ApiError,
     options::{apply_options, make_url, TiktokOptions},
+    transport::HttpRequest,
 };
 use itertools::Itertools;
-use reqwest::RequestBuilder;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
@@ -15,9 +15,9 @@ const URL: &str = "/video/list/";
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Body {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub cursor: Option<String>,
+    pub cursor: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub max_count: Option<String>,
+    pub max_count: Option<i32>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -37,19 +37,26 @@ impl Api {
     }
 
     #[allow(clippy::vec_init_then_push)]
-    pub fn build(self, bearer_code: &str) -> RequestBuilder {
+    pub fn build(self, bearer_code: &str) -> Result<HttpRequest, ApiError> {
+        check_range("max_count", self.body.max_count, 1, 20)?;
         let mut query_parameters = vec![];
         query_parameters.push(("fields", self.fields.iter().join(",")));
-        let client = reqwest::Client::new()
-            .post(make_url(URL, &self.options))
+        let request = HttpRequest::post(&make_url(URL, &self.options))
             .query(&query_parameters)
-            .json(&self.body)
-            .bearer_auth(bearer_code);
-        apply_options(client, &self.options)
+            .json(&self.body)?
+            .bearer_auth(bearer_code)?;
+        Ok(apply_options(request, &self.options))
+    }
+
+    pub async fn execute(self, bearer_code: &str) -> Result<ApiResponse<Response>, ApiError> {
+        let options = self.options.clone();
+        execute_api(self.build(bearer_code)?, &options).await
     }
 
-    pub async fn execute(self, bearer_code: &str) -> Result<Response, ApiError> {
-        execute_api(self.build(bearer_code)).await
+    #[cfg(feature = "blocking")]
+    pub fn execute_blocking(self, bearer_code: &str) -> Result<ApiResponse<Response>, ApiError> {
+        let options = self.options.clone();
+        crate::blocking::execute_api(self.build(bearer_code)?, &options)
     }
 }
 
@@ -85,12 +92,30 @@ impl Response {
         }
         res
     }
+
+    // TikTok returns cursor and has_more inside data; older responses had them
+    // at the top level.
+    pub fn next_cursor(&self) -> Option<i64> {
+        self.data.as_ref().and_then(|it| it.cursor).or(self.cursor)
+    }
+
+    pub fn has_more(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|it| it.has_more)
+            .or(self.has_more)
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Data {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub videos: Option<Vec<Video>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
     #[serde(flatten)]
     pub extra: std::collections::HashMap<String, serde_json::Value>,
 }
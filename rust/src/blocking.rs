@@ -0,0 +1,117 @@
+use crate::{
+    apis::{after_send, before_send, ApiResponse},
+    error::Error,
+    options::{make_blocking_transport, TiktokOptions},
+    telemetry::Telemetry,
+    transport::{BlockingTransport, HttpRequest, HttpResponse},
+};
+use serde::de::DeserializeOwned;
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+    time::Instant,
+};
+
+pub fn execute_api<T>(
+    request: HttpRequest,
+    options: &Option<TiktokOptions>,
+) -> Result<ApiResponse<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let telemetry = Telemetry::new(&request, options);
+    let transport = make_blocking_transport(options);
+    let policy = options.as_ref().and_then(|it| it.retry.as_ref());
+    let started = Instant::now();
+    let deadline = policy.and_then(|it| it.deadline).map(|it| started + it);
+    let mut attempt = 0;
+    let res = telemetry.in_scope(|| loop {
+        attempt += 1;
+        let res = execute_once(
+            transport.as_ref(),
+            request.clone(),
+            options,
+            &telemetry,
+            deadline,
+        );
+        match res {
+            Err(err) => {
+                match policy.and_then(|it| it.retry_after(attempt, started.elapsed(), &err)) {
+                    Some(backoff) => std::thread::sleep(backoff),
+                    None => break Err(err),
+                }
+            }
+            res => break res,
+        }
+    });
+    telemetry.finish(attempt - 1, &res);
+    res
+}
+
+pub(crate) fn execute_oauth<T>(
+    request: HttpRequest,
+    options: &Option<TiktokOptions>,
+    parse: fn(HttpResponse) -> Result<T, Error>,
+) -> Result<T, Error> {
+    let telemetry = Telemetry::new(&request, options);
+    let res = telemetry.in_scope(|| {
+        make_blocking_transport(options)
+            .send(request)
+            .and_then(|response| {
+                telemetry.response(&response, None);
+                parse(response)
+            })
+    });
+    telemetry.finish(0, &res);
+    res
+}
+
+fn execute_once<T>(
+    transport: &dyn BlockingTransport,
+    request: HttpRequest,
+    options: &Option<TiktokOptions>,
+    telemetry: &Telemetry,
+    deadline: Option<Instant>,
+) -> Result<ApiResponse<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let mut request = request;
+    let research_quota = block_on(before_send(&mut request, options, deadline, |wait| {
+        std::thread::sleep(wait);
+        std::future::ready(())
+    }))?;
+    let started = Instant::now();
+    let response = transport.send(request)?;
+    block_on(after_send(
+        response,
+        started.elapsed(),
+        research_quota,
+        telemetry,
+    ))
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Drives the steps shared with the async calls. They only await the quota
+// store, see the QuotaStore requirements.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{
+    apis::{
+        get_v2_user_info, post_v2_video_list,
+        post_v2_video_query::{self, Filters},
+        ApiResponse,
+    },
+    error::Error,
+    options::{make_transport, TiktokOptions},
+    responses::{
+        error::Code,
+        projection::{project_videos, UserView, VideoView},
+        user::UserField,
+        video::{Video, VideoField},
+    },
+};
+use futures_core::Stream;
+use futures_util::StreamExt;
+use std::{
+    collections::{HashSet, VecDeque},
+    future::Future,
+};
+
+pub const VIDEO_LIST_MAX_COUNT: i32 = 20;
+pub const VIDEO_QUERY_MAX_IDS: usize = 20;
+
+#[derive(Debug, Clone, Default)]
+pub struct Paging {
+    // Videos per request, 1 to 20. TikTok uses 20 when unset.
+    pub page_size: Option<i32>,
+    // Stop after this many videos.
+    pub max_items: Option<usize>,
+}
+
+pub trait CredentialSource {
+    fn access_token(&self) -> impl Future<Output = Result<String, Error>> + Send;
+
+    // Called after TikTok rejected the given access token. Returning a new
+    // token retries the call once.
+    fn refresh_access_token(
+        &self,
+        _rejected: &str,
+    ) -> impl Future<Output = Result<Option<String>, Error>> + Send {
+        async { Ok(None) }
+    }
+
+    // Called when the access token was rejected and could not be replaced.
+    fn invalidate(&self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+}
+
+impl CredentialSource for String {
+    async fn access_token(&self) -> Result<String, Error> {
+        Ok(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TiktokClient<C> {
+    credentials: C,
+    options: TiktokOptions,
+}
+
+impl<C: CredentialSource + Sync> TiktokClient<C> {
+    pub fn new(credentials: C, options: Option<TiktokOptions>) -> Self {
+        let transport = make_transport(&options);
+        let mut options = options.unwrap_or_default();
+        options.transport = Some(transport);
+        Self {
+            credentials,
+            options,
+        }
+    }
+
+    pub fn credentials(&self) -> &C {
+        &self.credentials
+    }
+
+    pub fn options(&self) -> &TiktokOptions {
+        &self.options
+    }
+
+    pub async fn user_info(
+        &self,
+        fields: HashSet<UserField>,
+    ) -> Result<ApiResponse<get_v2_user_info::Response>, Error> {
+        self.call(|token| {
+            let api = get_v2_user_info::Api::new(fields.clone(), Some(self.options.clone()));
+            async move { api.execute(&token).await }
+        })
+        .await
+    }
+
+    pub async fn video_list(
+        &self,
+        fields: HashSet<VideoField>,
+        body: post_v2_video_list::Body,
+    ) -> Result<ApiResponse<post_v2_video_list::Response>, Error> {
+        self.call(|token| {
+            let api = post_v2_video_list::Api::new(
+                fields.clone(),
+                body.clone(),
+                Some(self.options.clone()),
+            );
+            async move { api.execute(&token).await }
+        })
+        .await
+    }
+
+    pub async fn video_query(
+        &self,
+        fields: HashSet<VideoField>,
+        video_ids: Vec<String>,
+    ) -> Result<ApiResponse<post_v2_video_query::Response>, Error> {
+        let body = post_v2_video_query::Body {
+            filters: Some(Filters {
+                video_ids: Some(video_ids),
+            }),
+        };
+        self.call(|token| {
+            let api = post_v2_video_query::Api::new(
+                fields.clone(),
+                body.clone(),
+                Some(self.options.clone()),
+            );
+            async move { api.execute(&token).await }
+        })
+        .await
+    }
+
+    pub async fn user_info_view(&self, fields: HashSet<UserField>) -> Result<UserView, Error> {
+        let res = self.user_info(fields.clone()).await?.into_inner();
+        let user = res
+            .data
+            .and_then(|it| it.user)
+            .ok_or_else(|| Error::MissingField("user".to_owned()))?;
+        Ok(UserView::new(user, fields))
+    }
+
+    // One page of the video list; pass cursor in the next body while has_more.
+    pub async fn video_list_view(
+        &self,
+        fields: HashSet<VideoField>,
+        body: post_v2_video_list::Body,
+    ) -> Result<VideoListView, Error> {
+        let res = self.video_list(fields.clone(), body).await?.into_inner();
+        let cursor = res.next_cursor();
+        let has_more = res.has_more();
+        let videos = res.data.and_then(|it| it.videos).unwrap_or_default();
+        Ok(VideoListView {
+            videos: project_videos(videos, &fields),
+            cursor,
+            has_more,
+        })
+    }
+
+    pub async fn video_query_view(
+        &self,
+        fields: HashSet<VideoField>,
+        video_ids: Vec<String>,
+    ) -> Result<Vec<VideoView>, Error> {
+        let res = self
+            .video_query(fields.clone(), video_ids)
+            .await?
+            .into_inner();
+        let videos = res.data.and_then(|it| it.videos).unwrap_or_default();
+        Ok(project_videos(videos, &fields))
+    }
+
+    // Queries any number of ids in batches of 20, running up to `concurrency`
+    // batches at once. Ids TikTok did not return are listed in missing_ids;
+    // batches that failed are listed in failures with their ids, and the
+    // videos of the other batches are still returned.
+    pub async fn video_query_all(
+        &self,
+        fields: HashSet<VideoField>,
+        video_ids: Vec<String>,
+        concurrency: usize,
+    ) -> VideoQueryResult {
+        let mut fields = fields;
+        fields.insert(VideoField::Id);
+        let mut seen = HashSet::new();
+        let video_ids: Vec<String> = video_ids
+            .into_iter()
+            .filter(|it| seen.insert(it.clone()))
+            .collect();
+        let responses: Vec<_> = futures_util::stream::iter(video_ids.chunks(VIDEO_QUERY_MAX_IDS))
+            .map(|chunk| {
+                let query = self.video_query(fields.clone(), chunk.to_vec());
+                async move { (chunk, query.await) }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+        let mut videos = vec![];
+        let mut failures = vec![];
+        for (chunk, res) in responses {
+            match res {
+                Ok(res) => videos.extend(
+                    res.into_inner()
+                        .data
+                        .and_then(|it| it.videos)
+                        .unwrap_or_default(),
+                ),
+                Err(error) => failures.push(BatchFailure {
+                    ids: chunk.to_vec(),
+                    error,
+                }),
+            }
+        }
+        let found: HashSet<&str> = videos.iter().filter_map(|it| it.id.as_deref()).collect();
+        let failed: HashSet<&str> = failures
+            .iter()
+            .flat_map(|it| it.ids.iter().map(|it| it.as_str()))
+            .collect();
+        let missing_ids = video_ids
+            .iter()
+            .filter(|it| !found.contains(it.as_str()) && !failed.contains(it.as_str()))
+            .cloned()
+            .collect();
+        VideoQueryResult {
+            videos,
+            missing_ids,
+            failures,
+        }
+    }
+
+    // Walks the video list page by page, passing each cursor to the next
+    // request until has_more is false. The stream ends after the first error.
+    pub fn video_list_stream(
+        &self,
+        fields: HashSet<VideoField>,
+        paging: Paging,
+    ) -> impl Stream<Item = Result<Video, Error>> + '_ {
+        let state = VideoListState {
+            cursor: None,
+            videos: VecDeque::new(),
+            done: false,
+            remaining: paging.max_items,
+        };
+        let page_size = paging.page_size.map(|it| it.clamp(1, VIDEO_LIST_MAX_COUNT));
+        futures_util::stream::unfold(state, move |mut state| {
+            let fields = fields.clone();
+            async move {
+                loop {
+                    if state.remaining == Some(0) {
+                        return None;
+                    }
+                    if let Some(video) = state.videos.pop_front() {
+                        state.remaining = state.remaining.map(|it| it - 1);
+                        return Some((Ok(video), state));
+                    }
+                    if state.done {
+                        return None;
+                    }
+                    let max_count = match state.remaining {
+                        Some(remaining) => {
+                            let limit = page_size.unwrap_or(VIDEO_LIST_MAX_COUNT);
+                            Some(i32::try_from(remaining).map_or(limit, |it| limit.min(it)))
+                        }
+                        None => page_size,
+                    };
+                    let body = post_v2_video_list::Body {
+                        cursor: state.cursor,
+                        max_count,
+                    };
+                    let res = match self.video_list(fields.clone(), body).await {
+                        Ok(res) => res.into_inner(),
+                        Err(err) => {
+                            state.done = true;
+                            return Some((Err(err), state));
+                        }
+                    };
+                    let cursor = res.next_cursor();
+                    let has_more = res.has_more();
+                    let videos = res.data.and_then(|it| it.videos).unwrap_or_default();
+                    state.done = !has_more || cursor.is_none() || videos.is_empty();
+                    state.cursor = cursor;
+                    state.videos.extend(videos);
+                }
+            }
+        })
+    }
+
+    async fn call<T, F, Fut>(&self, f: F) -> Result<T, Error>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let token = self.credentials.access_token().await?;
+        let res = f(token.clone()).await;
+        if !is_access_token_invalid(&res) {
+            return res;
+        }
+        let Some(token) = self.credentials.refresh_access_token(&token).await? else {
+            self.credentials.invalidate().await;
+            return res;
+        };
+        let res = f(token).await;
+        if is_access_token_invalid(&res) {
+            self.credentials.invalidate().await;
+        }
+        res
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VideoListView {
+    pub videos: Vec<VideoView>,
+    pub cursor: Option<i64>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct VideoQueryResult {
+    pub videos: Vec<Video>,
+    pub missing_ids: Vec<String>,
+    pub failures: Vec<BatchFailure>,
+}
+
+impl VideoQueryResult {
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+// A batch of ids whose request failed.
+#[derive(Debug)]
+pub struct BatchFailure {
+    pub ids: Vec<String>,
+    pub error: Error,
+}
+
+struct VideoListState {
+    cursor: Option<i64>,
+    videos: VecDeque<Video>,
+    done: bool,
+    remaining: Option<usize>,
+}
+
+fn is_access_token_invalid<T>(res: &Result<T, Error>) -> bool {
+    matches!(res, Err(Error::Api(err, _)) if matches!(err.code, Some(Code::AccessTokenInvalid)))
+}
@@ -1,14 +1,21 @@
-use reqwest::StatusCode;
-use serde::{Deserialize, Serialize};
+use crate::responses::error::Code;
+use http::StatusCode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
+    // The sources are boxed so custom transports can report their own
+    // timeout and connection failures.
     #[error("Timeout")]
-    Timeout,
+    Timeout(#[source] Box<dyn std::error::Error + Send + Sync>),
 
+    #[error("Connection {0}")]
+    Connection(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    // The body text, the status and the x-tt-logid header.
     #[error("Other {0}")]
-    Other(String, StatusCode),
+    Other(String, StatusCode, Option<String>),
 
     #[error("OAuth {0:?}, {1}")]
     OAuth(OAuthError, StatusCode),
@@ -16,16 +23,270 @@ pub enum Error {
     #[error("ApiError {0:?}, {1}")]
     Api(crate::responses::error::Error, StatusCode),
 
+    #[cfg(feature = "reqwest")]
     #[error("reqwest {0}")]
-    Reqwest(#[from] reqwest::Error),
+    Reqwest(#[source] reqwest::Error),
 
     #[error("serde json {0}")]
     Json(#[from] serde_json::Error),
+
+    #[error("Validation {0}")]
+    Validation(String),
+
+    #[error("Field not requested {0}")]
+    FieldNotRequested(String),
+
+    #[error("Missing field {0}")]
+    MissingField(String),
+
+    #[error("Transport {0}")]
+    Transport(String),
+
+    #[error("TokenStore {0}")]
+    TokenStore(String),
+
+    #[error("Reauthorization required {0}")]
+    ReauthorizationRequired(String),
+
+    #[error("Quota exhausted {0:?}")]
+    QuotaExhausted(crate::quota::QuotaUsage),
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            Self::Timeout(err.into())
+        } else if err.is_connect() {
+            Self::Connection(err.into())
+        } else {
+            Self::Reqwest(err)
+        }
+    }
+}
+
+impl Error {
+    // Transient failures worth retrying: timeouts, connection failures, 5xx,
+    // internal errors and rate limiting.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Timeout(_) | Self::Connection(_) => true,
+            Self::Api(err, status_code) => {
+                status_code.is_server_error()
+                    || self.is_rate_limited()
+                    || matches!(err.code, Some(Code::InternalError))
+            }
+            Self::Other(_, status_code, _) => {
+                status_code.is_server_error() || self.is_rate_limited()
+            }
+            _ => false,
+        }
+    }
+
+    // The access token or the granted scopes were rejected.
+    pub fn is_auth_error(&self) -> bool {
+        match self {
+            Self::Api(err, status_code) => {
+                *status_code == StatusCode::UNAUTHORIZED
+                    || matches!(
+                        err.code,
+                        Some(Code::AccessTokenInvalid)
+                            | Some(Code::ScopeNotAuthorized)
+                            | Some(Code::ScopePermissionMissed)
+                            | Some(Code::TokenNotAuthorizedForSpecifiedDeeplink)
+                    )
+            }
+            Self::Other(_, status_code, _) => *status_code == StatusCode::UNAUTHORIZED,
+            Self::OAuth(err, _) => err.requires_reauthorization(),
+            Self::ReauthorizationRequired(_) => true,
+            _ => false,
+        }
+    }
+
+    // The request itself or the app configuration is wrong; retrying the same
+    // request will not help.
+    pub fn is_client_error(&self) -> bool {
+        match self {
+            Self::Api(err, status_code) => {
+                !self.is_auth_error()
+                    && !self.is_rate_limited()
+                    && !status_code.is_server_error()
+                    && (status_code.is_client_error()
+                        || !matches!(err.code, None | Some(Code::Ok) | Some(Code::InternalError)))
+            }
+            Self::Other(_, status_code, _) => {
+                status_code.is_client_error() && !self.is_auth_error() && !self.is_rate_limited()
+            }
+            Self::OAuth(err, _) => err.is_configuration_error(),
+            Self::Validation(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            Self::Api(err, status_code) => {
+                *status_code == StatusCode::TOO_MANY_REQUESTS
+                    || matches!(err.code, Some(Code::RateLimitExceeded))
+            }
+            Self::Other(_, status_code, _) => *status_code == StatusCode::TOO_MANY_REQUESTS,
+            _ => false,
+        }
+    }
+
+    pub fn is_quota_exhausted(&self) -> bool {
+        matches!(self, Self::QuotaExhausted(_))
+    }
+
+    // Only a new authorization by the user resolves the error.
+    pub fn requires_reauthorization(&self) -> bool {
+        match self {
+            Self::Api(err, _) => matches!(
+                err.code,
+                Some(Code::ScopeNotAuthorized) | Some(Code::ScopePermissionMissed)
+            ),
+            Self::OAuth(err, _) => err.requires_reauthorization(),
+            Self::ReauthorizationRequired(_) => true,
+            _ => false,
+        }
+    }
+
+    // The log id to quote to TikTok support.
+    pub fn log_id(&self) -> Option<&str> {
+        match self {
+            Self::Api(err, _) => err.log_id.as_deref(),
+            Self::OAuth(err, _) => Some(err.log_id.as_str()),
+            Self::Other(_, _, log_id) => log_id.as_deref(),
+            _ => None,
+        }
+        .filter(|it| !it.is_empty())
+    }
+
+    // Fills in the x-tt-logid header where the body carried no log id.
+    pub(crate) fn with_log_id(self, log_id: Option<String>) -> Self {
+        let Some(log_id) = log_id else {
+            return self;
+        };
+        match self {
+            Self::Api(mut err, status_code) => {
+                if err.log_id.as_deref().unwrap_or_default().is_empty() {
+                    err.log_id = Some(log_id);
+                }
+                Self::Api(err, status_code)
+            }
+            Self::OAuth(mut err, status_code) => {
+                if err.log_id.is_empty() {
+                    err.log_id = log_id;
+                }
+                Self::OAuth(err, status_code)
+            }
+            Self::Other(text, status_code, None) => Self::Other(text, status_code, Some(log_id)),
+            err => err,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OAuthError {
-    pub error: String,
+    pub error: OAuthErrorCode,
+    #[serde(default)]
     pub error_description: String,
+    #[serde(default)]
     pub log_id: String,
 }
+
+impl OAuthError {
+    pub fn kind(&self) -> OAuthErrorKind {
+        self.error.kind()
+    }
+
+    pub fn requires_reauthorization(&self) -> bool {
+        self.error.requires_reauthorization()
+    }
+
+    pub fn is_configuration_error(&self) -> bool {
+        self.error.is_configuration_error()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OAuthErrorKind {
+    // The grant or scope is no longer valid; the user must authorize again.
+    Reauthorize,
+    // The request or client credentials are wrong; fix the app configuration.
+    Configuration,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OAuthErrorCode {
+    InvalidRequest,
+    InvalidClient,
+    InvalidGrant,
+    UnauthorizedClient,
+    UnsupportedGrantType,
+    InvalidScope,
+    Unknown(String),
+}
+
+impl OAuthErrorCode {
+    pub fn kind(&self) -> OAuthErrorKind {
+        match self {
+            Self::InvalidGrant | Self::InvalidScope => OAuthErrorKind::Reauthorize,
+            Self::InvalidRequest
+            | Self::InvalidClient
+            | Self::UnauthorizedClient
+            | Self::UnsupportedGrantType => OAuthErrorKind::Configuration,
+            Self::Unknown(_) => OAuthErrorKind::Unknown,
+        }
+    }
+
+    pub fn requires_reauthorization(&self) -> bool {
+        self.kind() == OAuthErrorKind::Reauthorize
+    }
+
+    pub fn is_configuration_error(&self) -> bool {
+        self.kind() == OAuthErrorKind::Configuration
+    }
+}
+
+impl std::fmt::Display for OAuthErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidRequest => write!(f, "invalid_request"),
+            Self::InvalidClient => write!(f, "invalid_client"),
+            Self::InvalidGrant => write!(f, "invalid_grant"),
+            Self::UnauthorizedClient => write!(f, "unauthorized_client"),
+            Self::UnsupportedGrantType => write!(f, "unsupported_grant_type"),
+            Self::InvalidScope => write!(f, "invalid_scope"),
+            Self::Unknown(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<&str> for OAuthErrorCode {
+    fn from(value: &str) -> Self {
+        match value {
+            "invalid_request" => Self::InvalidRequest,
+            "invalid_client" => Self::InvalidClient,
+            "invalid_grant" => Self::InvalidGrant,
+            "unauthorized_client" => Self::UnauthorizedClient,
+            "unsupported_grant_type" => Self::UnsupportedGrantType,
+            "invalid_scope" => Self::InvalidScope,
+            _ => Self::Unknown(value.to_owned()),
+        }
+    }
+}
+
+impl Serialize for OAuthErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for OAuthErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(Self::from(value.as_str()))
+    }
+}
@@ -1,5 +1,18 @@
 pub mod apis;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod client;
 pub mod error;
+pub mod metrics;
 pub mod oauth;
 pub mod options;
+pub mod quota;
+pub mod rate_limit;
 pub mod responses;
+pub mod retry;
+#[cfg(feature = "tower")]
+pub mod service;
+pub mod session;
+mod telemetry;
+pub mod token_store;
+pub mod transport;
@@ -0,0 +1,86 @@
+use crate::error::Error;
+use http::{Method, StatusCode};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct CallRecord<'a> {
+    pub endpoint: &'a str,
+    pub method: &'a Method,
+    pub status: Option<StatusCode>,
+    pub latency: Duration,
+    pub retries: u32,
+    pub error: Option<&'a Error>,
+}
+
+impl CallRecord<'_> {
+    // "ok" for success, the TikTok or OAuth error code, or the kind of failure.
+    pub fn code(&self) -> String {
+        match self.error {
+            None => "ok".to_owned(),
+            Some(Error::Api(err, _)) => err
+                .code
+                .as_ref()
+                .map(|it| it.to_string())
+                .unwrap_or("unknown".to_owned()),
+            Some(Error::OAuth(err, _)) => err.error.to_string(),
+            Some(Error::Timeout(_)) => "timeout".to_owned(),
+            Some(Error::Connection(_)) => "connection".to_owned(),
+            Some(Error::Other(..)) => "other".to_owned(),
+            #[cfg(feature = "reqwest")]
+            Some(Error::Reqwest(_)) => "reqwest".to_owned(),
+            Some(Error::Json(_)) => "json".to_owned(),
+            Some(Error::Validation(_)) => "validation".to_owned(),
+            Some(Error::FieldNotRequested(_)) => "field_not_requested".to_owned(),
+            Some(Error::MissingField(_)) => "missing_field".to_owned(),
+            Some(Error::Transport(_)) => "transport".to_owned(),
+            Some(Error::TokenStore(_)) => "token_store".to_owned(),
+            Some(Error::ReauthorizationRequired(_)) => "reauthorization_required".to_owned(),
+            Some(Error::QuotaExhausted(_)) => "quota_exhausted".to_owned(),
+        }
+    }
+}
+
+// Called once per API or OAuth call, after retries.
+pub trait MetricsObserver: std::fmt::Debug + Send + Sync {
+    fn record(&self, call: &CallRecord<'_>);
+}
+
+// Records tiktok_api_requests_total and tiktok_api_errors_total counters and
+// a tiktok_api_latency_seconds histogram through the metrics crate.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Default)]
+pub struct MetricsCrateObserver;
+
+#[cfg(feature = "metrics")]
+impl MetricsObserver for MetricsCrateObserver {
+    fn record(&self, call: &CallRecord<'_>) {
+        let endpoint = call.endpoint.to_owned();
+        let method = call.method.to_string();
+        let status = call
+            .status
+            .map(|it| it.as_u16().to_string())
+            .unwrap_or("none".to_owned());
+        let code = call.code();
+        ::metrics::counter!(
+            "tiktok_api_requests_total",
+            "endpoint" => endpoint.clone(),
+            "method" => method.clone(),
+            "status" => status,
+        )
+        .increment(1);
+        ::metrics::histogram!(
+            "tiktok_api_latency_seconds",
+            "endpoint" => endpoint.clone(),
+            "method" => method,
+        )
+        .record(call.latency.as_secs_f64());
+        if call.error.is_some() {
+            ::metrics::counter!(
+                "tiktok_api_errors_total",
+                "endpoint" => endpoint,
+                "code" => code,
+            )
+            .increment(1);
+        }
+    }
+}
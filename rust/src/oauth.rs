@@ -1,12 +1,17 @@
+#[cfg(feature = "blocking")]
+use crate::blocking::execute_oauth as execute_oauth_blocking;
 use crate::{
-    error::{Error, OAuthError},
-    options::{apply_options, make_url, TiktokOptions},
+    error::{Error, OAuthError, OAuthErrorCode},
+    options::{apply_options, make_transport, make_url, TiktokOptions},
+    responses::error::Code,
+    telemetry::Telemetry,
+    transport::{HttpRequest, HttpResponse},
 };
 use base64::prelude::{Engine as _, BASE64_URL_SAFE_NO_PAD};
+use http::{header::CACHE_CONTROL, StatusCode};
 use itertools::Itertools;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use rand::Rng;
-use reqwest::header::CACHE_CONTROL;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -61,7 +66,7 @@ pub struct OAuthUrlResult {
     pub csrf_token: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TokenResult {
     pub open_id: String,
     pub scope: String,
@@ -72,6 +77,26 @@ pub struct TokenResult {
     pub token_type: String,
 }
 
+impl std::fmt::Debug for TokenResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenResult")
+            .field("open_id", &self.open_id)
+            .field("scope", &self.scope)
+            .field("access_token", &"[REDACTED]")
+            .field("expires_in", &self.expires_in)
+            .field("refresh_token", &"[REDACTED]")
+            .field("refresh_expires_in", &self.refresh_expires_in)
+            .field("token_type", &self.token_type)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevokeResult {
+    Revoked,
+    AlreadyInvalid,
+}
+
 pub struct TiktokOauth {
     scopes: Vec<TiktokScope>,
     client_key: String,
@@ -121,66 +146,156 @@ impl TiktokOauth {
     }
 
     pub async fn token(&self, code: &str) -> Result<TokenResult, Error> {
+        let request = make_request(TOKEN_URL, &self.token_form(code), &self.options)?;
+        execute_oauth(request, &self.options, token_response).await
+    }
+
+    pub async fn refresh(&self, refresh_token: &str) -> Result<TokenResult, Error> {
+        let request = make_request(TOKEN_URL, &self.refresh_form(refresh_token), &self.options)?;
+        execute_oauth(request, &self.options, token_response).await
+    }
+
+    pub async fn revoke(&self, access_token: &str) -> Result<RevokeResult, Error> {
+        let request = make_request(REVOKE_URL, &self.revoke_form(access_token), &self.options)?;
+        execute_oauth(request, &self.options, revoke_response).await
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn token_blocking(&self, code: &str) -> Result<TokenResult, Error> {
+        let request = make_request(TOKEN_URL, &self.token_form(code), &self.options)?;
+        execute_oauth_blocking(request, &self.options, token_response)
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn refresh_blocking(&self, refresh_token: &str) -> Result<TokenResult, Error> {
+        let request = make_request(TOKEN_URL, &self.refresh_form(refresh_token), &self.options)?;
+        execute_oauth_blocking(request, &self.options, token_response)
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn revoke_blocking(&self, access_token: &str) -> Result<RevokeResult, Error> {
+        let request = make_request(REVOKE_URL, &self.revoke_form(access_token), &self.options)?;
+        execute_oauth_blocking(request, &self.options, revoke_response)
+    }
+
+    fn token_form<'a>(&'a self, code: &'a str) -> HashMap<&'a str, &'a str> {
         let mut form = HashMap::new();
         form.insert("client_key", self.client_key.as_str());
         form.insert("client_secret", self.client_secret.as_str());
         form.insert("grant_type", "authorization_code");
         form.insert("code", code);
         form.insert("redirect_uri", self.callback_url.as_str());
-        execute_token(form, &self.options).await
+        form
     }
 
-    pub async fn refresh(&self, refresh_token: &str) -> Result<TokenResult, Error> {
+    fn refresh_form<'a>(&'a self, refresh_token: &'a str) -> HashMap<&'a str, &'a str> {
         let mut form = HashMap::new();
         form.insert("client_key", self.client_key.as_str());
         form.insert("client_secret", self.client_secret.as_str());
         form.insert("grant_type", "refresh_token");
         form.insert("refresh_token", refresh_token);
-        execute_token(form, &self.options).await
+        form
     }
 
-    pub async fn revoke(&self, access_token: &str) -> Result<(), Error> {
+    fn revoke_form<'a>(&'a self, access_token: &'a str) -> HashMap<&'a str, &'a str> {
         let mut form = HashMap::new();
         form.insert("client_key", self.client_key.as_str());
         form.insert("client_secret", self.client_secret.as_str());
         form.insert("token", access_token);
-        let response = execute_send(REVOKE_URL, &form, &self.options).await?;
-        let status_code = response.status();
-        if status_code.is_success() {
-            Ok(())
-        } else {
-            let json = response.json().await?;
-            let token_error: OAuthError = serde_json::from_value(json)?;
-            Err(Error::OAuth(token_error, status_code))
-        }
+        form
     }
 }
 
-async fn execute_send(
+fn make_request(
     url: &str,
     form: &HashMap<&str, &str>,
     options: &Option<TiktokOptions>,
-) -> Result<reqwest::Response, reqwest::Error> {
-    let builder = reqwest::Client::new()
-        .post(make_url(url, options))
-        .header(CACHE_CONTROL, "no-cache")
-        .form(form);
-    apply_options(builder, options).send().await
+) -> Result<HttpRequest, Error> {
+    let request = HttpRequest::post(&make_url(url, options))
+        .header(CACHE_CONTROL, "no-cache")?
+        .form(form)?;
+    Ok(apply_options(request, options))
 }
 
-async fn execute_token(
-    form: HashMap<&str, &str>,
+async fn execute_oauth<T>(
+    request: HttpRequest,
     options: &Option<TiktokOptions>,
-) -> Result<TokenResult, Error> {
-    let response = execute_send(TOKEN_URL, &form, options).await?;
-    let status_code = response.status();
-    let json = response.json().await?;
-    if status_code.is_success() {
-        let token_result: TokenResult = serde_json::from_value(json)?;
-        Ok(token_result)
-    } else {
-        let token_error: OAuthError = serde_json::from_value(json)?;
-        Err(Error::OAuth(token_error, status_code))
+    parse: fn(HttpResponse) -> Result<T, Error>,
+) -> Result<T, Error> {
+    let telemetry = Telemetry::new(&request, options);
+    let res = match telemetry
+        .instrument(make_transport(options).send(request))
+        .await
+    {
+        Ok(response) => {
+            telemetry.response(&response, None);
+            parse(response)
+        }
+        Err(err) => Err(err),
+    };
+    telemetry.finish(0, &res);
+    res
+}
+
+pub(crate) fn token_response(response: HttpResponse) -> Result<TokenResult, Error> {
+    let status_code = response.status;
+    let text = response.text();
+    if let Some(err) = parse_error(&text, status_code) {
+        return Err(err.with_log_id(response.header_log_id()));
+    }
+    // The body holds the tokens, so it must not end up in the error.
+    Ok(serde_json::from_str::<TokenResult>(&text)?)
+}
+
+pub(crate) fn revoke_response(response: HttpResponse) -> Result<RevokeResult, Error> {
+    match parse_error(&response.text(), response.status) {
+        None => Ok(RevokeResult::Revoked),
+        Some(Error::OAuth(err, _)) if err.error == OAuthErrorCode::InvalidGrant => {
+            Ok(RevokeResult::AlreadyInvalid)
+        }
+        Some(Error::Api(err, _)) if matches!(err.code, Some(Code::AccessTokenInvalid)) => {
+            Ok(RevokeResult::AlreadyInvalid)
+        }
+        Some(err) => Err(err.with_log_id(response.header_log_id())),
+    }
+}
+
+// TikTok reports OAuth failures either as a flat {"error": "..."} body, as an
+// API style {"error": {"code": "..."}} body, or as non JSON text from a gateway,
+// sometimes with a 200 status. Only non 2xx bodies are kept as text; a 2xx body
+// may hold tokens.
+fn parse_error(text: &str, status_code: StatusCode) -> Option<Error> {
+    let unparsed = |err: serde_json::Error| {
+        if status_code.is_success() {
+            Error::Json(err)
+        } else {
+            Error::Other(text.to_owned(), status_code, None)
+        }
+    };
+    let json = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(json) => json,
+        Err(_) if status_code.is_success() && text.trim().is_empty() => return None,
+        Err(err) => return Some(unparsed(err)),
+    };
+    match &json["error"] {
+        serde_json::Value::String(error) if !error.is_empty() => {
+            match serde_json::from_value::<OAuthError>(json.clone()) {
+                Ok(err) => Some(Error::OAuth(err, status_code)),
+                Err(err) => Some(unparsed(err)),
+            }
+        }
+        serde_json::Value::Object(_) => {
+            match serde_json::from_value::<crate::responses::error::Error>(json["error"].clone()) {
+                Ok(err) if !matches!(err.code, None | Some(Code::Ok)) => {
+                    Some(Error::Api(err, status_code))
+                }
+                Ok(_) if status_code.is_success() => None,
+                Ok(_) => Some(Error::Other(text.to_owned(), status_code, None)),
+                Err(err) => Some(unparsed(err)),
+            }
+        }
+        _ if status_code.is_success() => None,
+        _ => Some(Error::Other(text.to_owned(), status_code, None)),
     }
 }
 
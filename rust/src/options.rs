@@ -1,6 +1,22 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use reqwest::RequestBuilder;
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+use crate::transport::BlockingReqwestTransport;
+#[cfg(feature = "blocking")]
+use crate::transport::BlockingTransport;
+#[cfg(not(feature = "reqwest"))]
+use crate::transport::MissingTransport;
+#[cfg(feature = "reqwest")]
+use crate::transport::ReqwestTransport;
+use crate::{
+    metrics::MetricsObserver,
+    quota::ResearchQuota,
+    rate_limit::RateLimiter,
+    retry::RetryPolicy,
+    transport::{HttpRequest, Transport},
+};
+#[cfg(feature = "reqwest")]
+use reqwest::Client;
 
 const URL_PREFIX: &str = "https://open.tiktokapis.com/v2";
 const ENV_KEY: &str = "TICTOK_V2_PREFIX_API";
@@ -9,6 +25,15 @@ const ENV_KEY: &str = "TICTOK_V2_PREFIX_API";
 pub struct TiktokOptions {
     pub prefix_url: Option<String>,
     pub timeout: Option<Duration>,
+    #[cfg(feature = "reqwest")]
+    pub client: Option<Client>,
+    pub transport: Option<Arc<dyn Transport>>,
+    #[cfg(feature = "blocking")]
+    pub blocking_transport: Option<Arc<dyn BlockingTransport>>,
+    pub retry: Option<RetryPolicy>,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    pub research_quota: Option<ResearchQuota>,
+    pub metrics: Option<Arc<dyn MetricsObserver>>,
 }
 
 pub fn clear_prefix_url() {
@@ -44,15 +69,51 @@ fn make_url_with_prefix(
     format!("{}{}", prefix_url, postfix_url)
 }
 
-pub(crate) fn apply_options(
-    client: RequestBuilder,
+#[cfg(feature = "reqwest")]
+pub(crate) fn make_client(options: &Option<TiktokOptions>) -> Client {
+    options
+        .as_ref()
+        .and_then(|it| it.client.clone())
+        .unwrap_or_default()
+}
+
+pub(crate) fn make_transport(options: &Option<TiktokOptions>) -> Arc<dyn Transport> {
+    if let Some(transport) = options.as_ref().and_then(|it| it.transport.clone()) {
+        return transport;
+    }
+    #[cfg(feature = "reqwest")]
+    return Arc::new(ReqwestTransport::new(make_client(options)));
+    #[cfg(not(feature = "reqwest"))]
+    Arc::new(MissingTransport)
+}
+
+// The blocking client starts its own runtime thread, so one is shared by default.
+#[cfg(feature = "blocking")]
+pub(crate) fn make_blocking_transport(
     options: &Option<TiktokOptions>,
-) -> RequestBuilder {
+) -> Arc<dyn BlockingTransport> {
+    if let Some(transport) = options
+        .as_ref()
+        .and_then(|it| it.blocking_transport.clone())
+    {
+        return transport;
+    }
+    #[cfg(feature = "reqwest")]
+    {
+        static CLIENT: std::sync::OnceLock<reqwest::blocking::Client> = std::sync::OnceLock::new();
+        let client = CLIENT.get_or_init(reqwest::blocking::Client::new).clone();
+        Arc::new(BlockingReqwestTransport::new(client))
+    }
+    #[cfg(not(feature = "reqwest"))]
+    Arc::new(MissingTransport)
+}
+
+pub(crate) fn apply_options(request: HttpRequest, options: &Option<TiktokOptions>) -> HttpRequest {
     let Some(options) = options else {
-        return client;
+        return request;
     };
     let Some(timeout) = options.timeout else {
-        return client;
+        return request;
     };
-    client.timeout(timeout)
+    request.timeout(timeout)
 }
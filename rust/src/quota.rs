@@ -0,0 +1,238 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const RESEARCH_DAILY_REQUESTS: u64 = 1000;
+pub const RESEARCH_DAILY_RECORDS: u64 = 100_000;
+const RESEARCH_PATH: &str = "/research/";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub requests: u64,
+    pub records: u64,
+}
+
+impl QuotaUsage {
+    pub fn new(requests: u64, records: u64) -> Self {
+        Self { requests, records }
+    }
+
+    // Whether `usage` can be added without going over the request limit while
+    // records are still below theirs.
+    pub fn can_add(&self, usage: QuotaUsage, limit: QuotaUsage) -> bool {
+        self.requests + usage.requests <= limit.requests && self.records < limit.records
+    }
+}
+
+pub type QuotaFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+// Persists usage per app and UTC day, where day is days since the Unix epoch.
+// `add` must increment atomically and return the new total. `add_within` does
+// the same only when QuotaUsage::can_add allows it against the limit, checking
+// and adding atomically, and otherwise fails with Error::QuotaExhausted and the
+// current usage.
+//
+// The blocking API polls these futures on the calling thread without an async
+// runtime. A store used with `execute_blocking` must not depend on tokio I/O or
+// timers; for Redis or a database use a client with its own blocking calls or
+// only use the store from async code.
+pub trait QuotaStore: Send + Sync {
+    fn load<'a>(&'a self, app: &'a str, day: u64) -> QuotaFuture<'a, QuotaUsage>;
+
+    fn add<'a>(&'a self, app: &'a str, day: u64, usage: QuotaUsage) -> QuotaFuture<'a, QuotaUsage>;
+
+    fn add_within<'a>(
+        &'a self,
+        app: &'a str,
+        day: u64,
+        usage: QuotaUsage,
+        limit: QuotaUsage,
+    ) -> QuotaFuture<'a, QuotaUsage>;
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryQuotaStore {
+    usages: Mutex<HashMap<(String, u64), QuotaUsage>>,
+}
+
+impl MemoryQuotaStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl QuotaStore for MemoryQuotaStore {
+    fn load<'a>(&'a self, app: &'a str, day: u64) -> QuotaFuture<'a, QuotaUsage> {
+        let usages = self.usages.lock().unwrap_or_else(|err| err.into_inner());
+        let usage = usages
+            .get(&(app.to_owned(), day))
+            .copied()
+            .unwrap_or_default();
+        Box::pin(async move { Ok(usage) })
+    }
+
+    fn add<'a>(&'a self, app: &'a str, day: u64, usage: QuotaUsage) -> QuotaFuture<'a, QuotaUsage> {
+        let total = self.add_if(app, day, usage, |_| true);
+        Box::pin(async move { total })
+    }
+
+    fn add_within<'a>(
+        &'a self,
+        app: &'a str,
+        day: u64,
+        usage: QuotaUsage,
+        limit: QuotaUsage,
+    ) -> QuotaFuture<'a, QuotaUsage> {
+        let total = self.add_if(app, day, usage, |total| total.can_add(usage, limit));
+        Box::pin(async move { total })
+    }
+}
+
+impl MemoryQuotaStore {
+    fn add_if(
+        &self,
+        app: &str,
+        day: u64,
+        usage: QuotaUsage,
+        allow: impl FnOnce(&QuotaUsage) -> bool,
+    ) -> Result<QuotaUsage, Error> {
+        let mut usages = self.usages.lock().unwrap_or_else(|err| err.into_inner());
+        // Keep only the current day so the map does not grow forever.
+        usages.retain(|(_, it), _| *it >= day);
+        let total = usages.entry((app.to_owned(), day)).or_default();
+        if !allow(total) {
+            return Err(Error::QuotaExhausted(*total));
+        }
+        total.requests += usage.requests;
+        total.records += usage.records;
+        Ok(*total)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaMode {
+    // Only count requests and records.
+    Track,
+    // Refuse research requests once the daily budget is used up.
+    Enforce,
+}
+
+#[derive(Clone)]
+pub struct ResearchQuota {
+    app: String,
+    store: Arc<dyn QuotaStore>,
+    limit: QuotaUsage,
+    mode: QuotaMode,
+}
+
+impl ResearchQuota {
+    pub fn new(app: &str, store: Arc<dyn QuotaStore>) -> Self {
+        Self {
+            app: app.to_owned(),
+            store,
+            limit: QuotaUsage::new(RESEARCH_DAILY_REQUESTS, RESEARCH_DAILY_RECORDS),
+            mode: QuotaMode::Track,
+        }
+    }
+
+    pub fn limit(mut self, limit: QuotaUsage) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn mode(mut self, mode: QuotaMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn app(&self) -> &str {
+        &self.app
+    }
+
+    pub fn is_research_path(path: &str) -> bool {
+        path.contains(RESEARCH_PATH)
+    }
+
+    pub async fn usage(&self) -> Result<QuotaUsage, Error> {
+        self.store.load(&self.app, utc_day()).await
+    }
+
+    pub async fn remaining(&self) -> Result<QuotaUsage, Error> {
+        let usage = self.usage().await?;
+        Ok(QuotaUsage::new(
+            self.limit.requests.saturating_sub(usage.requests),
+            self.limit.records.saturating_sub(usage.records),
+        ))
+    }
+
+    pub async fn check(&self) -> Result<(), Error> {
+        if self.mode == QuotaMode::Track {
+            return Ok(());
+        }
+        let usage = self.usage().await?;
+        if usage.requests >= self.limit.requests || usage.records >= self.limit.records {
+            return Err(Error::QuotaExhausted(usage));
+        }
+        Ok(())
+    }
+
+    pub async fn record(&self, usage: QuotaUsage) -> Result<QuotaUsage, Error> {
+        self.store.add(&self.app, utc_day(), usage).await
+    }
+
+    // Takes one request from the budget before sending. In Enforce mode the
+    // store checks and adds in one call, so concurrent requests cannot all pass
+    // a check and then go over the limit together, and refused requests are not
+    // counted.
+    pub async fn reserve(&self) -> Result<(), Error> {
+        if self.mode == QuotaMode::Track {
+            return Ok(());
+        }
+        self.store
+            .add_within(&self.app, utc_day(), QuotaUsage::new(1, 0), self.limit)
+            .await?;
+        Ok(())
+    }
+
+    // Adds the records of a response to a request that went through reserve.
+    pub async fn complete(&self, records: u64) -> Result<QuotaUsage, Error> {
+        let requests = match self.mode {
+            QuotaMode::Track => 1,
+            QuotaMode::Enforce => 0,
+        };
+        self.record(QuotaUsage::new(requests, records)).await
+    }
+}
+
+impl std::fmt::Debug for ResearchQuota {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResearchQuota")
+            .field("app", &self.app)
+            .field("limit", &self.limit)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+pub fn utc_day() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|it| it.as_secs() / 86400)
+        .unwrap_or_default()
+}
+
+// Research responses return their records as the array inside data, for
+// example data.videos or data.comments.
+pub(crate) fn count_records(json: &serde_json::Value) -> u64 {
+    json["data"]
+        .as_object()
+        .and_then(|data| data.values().find_map(|it| it.as_array()))
+        .map(|it| it.len() as u64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,205 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const MINUTE: Duration = Duration::from_secs(60);
+const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+const PRUNE_AT: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quota {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl Quota {
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        Self { capacity, period }
+    }
+
+    pub fn per_minute(capacity: u32) -> Self {
+        Self::new(capacity, MINUTE)
+    }
+
+    pub fn per_day(capacity: u32) -> Self {
+        Self::new(capacity, DAY)
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.capacity as f64 / self.period.as_secs_f64()
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    quota: Quota,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.quota.refill_per_sec()).min(self.quota.capacity as f64);
+        self.updated_at = now;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.quota.capacity as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Limit {
+    bucket: String,
+    quota: Quota,
+    per_token: bool,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    map: HashMap<(String, Option<u64>), Bucket>,
+    prune_at: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    quotas: Vec<(String, Limit)>,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Quotas documented by TikTok. Display API quotas apply per access token;
+    // the research quota is one daily budget for the app across all research
+    // endpoints.
+    pub fn tiktok_default() -> Self {
+        Self::new()
+            .with_quota("/user/info/", Quota::per_minute(600))
+            .with_quota("/video/list/", Quota::per_minute(600))
+            .with_quota("/video/query/", Quota::per_minute(600))
+            .with_shared_quota(
+                "research",
+                &[
+                    "/research/video/query/",
+                    "/research/user/info/",
+                    "/research/video/comment/list/",
+                    "/research/user/liked_videos/",
+                    "/research/user/pinned_videos/",
+                    "/research/user/followers/",
+                    "/research/user/following/",
+                    "/research/user/reposted_videos/",
+                ],
+                Quota::per_day(1000),
+            )
+    }
+
+    // The path is matched against the end of the request path, so the same
+    // limiter works with any prefix url. The longest matching path wins, so
+    // /research/video/query/ is not limited as /video/query/.
+    pub fn with_quota(self, path: &str, quota: Quota) -> Self {
+        self.with_limit(
+            path,
+            Limit {
+                bucket: path.to_owned(),
+                quota,
+                per_token: true,
+            },
+        )
+    }
+
+    // All the paths share one bucket named `group`, whatever the access token.
+    pub fn with_shared_quota(mut self, group: &str, paths: &[&str], quota: Quota) -> Self {
+        for path in paths {
+            self = self.with_limit(
+                path,
+                Limit {
+                    bucket: group.to_owned(),
+                    quota,
+                    per_token: false,
+                },
+            );
+        }
+        self
+    }
+
+    fn with_limit(mut self, path: &str, limit: Limit) -> Self {
+        self.quotas.retain(|(it, _)| it != path);
+        self.quotas.push((path.to_owned(), limit));
+        self
+    }
+
+    pub fn quota(&self, path: &str) -> Option<Quota> {
+        self.find(path).map(|it| it.quota)
+    }
+
+    fn find(&self, path: &str) -> Option<&Limit> {
+        self.quotas
+            .iter()
+            .filter(|(it, _)| path.ends_with(it.as_str()))
+            .max_by_key(|(it, _)| it.len())
+            .map(|(_, limit)| limit)
+    }
+
+    // Waits until a request to the path with the given access token is allowed.
+    pub async fn acquire(&self, path: &str, bearer_code: &str) {
+        while let Some(wait) = self.try_acquire(path, bearer_code) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // Takes a token and returns None, or returns how long to wait for one.
+    pub fn try_acquire(&self, path: &str, bearer_code: &str) -> Option<Duration> {
+        let limit = self.find(path)?;
+        let quota = limit.quota;
+        if quota.capacity == 0 {
+            return None;
+        }
+        let token = limit.per_token.then(|| {
+            let mut hasher = DefaultHasher::new();
+            bearer_code.hash(&mut hasher);
+            hasher.finish()
+        });
+        let key = (limit.bucket.clone(), token);
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|err| err.into_inner());
+        buckets.prune(now);
+        let bucket = buckets.map.entry(key).or_insert_with(|| Bucket {
+            tokens: quota.capacity as f64,
+            updated_at: now,
+            quota,
+        });
+        bucket.refill(now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / quota.refill_per_sec(),
+            ))
+        }
+    }
+}
+
+impl Buckets {
+    // Access tokens rotate, so buckets keyed by old tokens pile up. A full
+    // bucket is the same as a missing one and is dropped once the map doubles.
+    fn prune(&mut self, now: Instant) {
+        if self.map.len() < self.prune_at.max(PRUNE_AT) {
+            return;
+        }
+        self.map.retain(|_, bucket| {
+            bucket.refill(now);
+            !bucket.is_full()
+        });
+        self.prune_at = self.map.len() * 2;
+    }
+}
@@ -1,3 +1,6 @@
 pub mod error;
+mod number;
+pub mod projection;
+mod timestamp;
 pub mod user;
 pub mod video;
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashSet;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -23,24 +23,25 @@ impl Error {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum Code {
-    #[serde(rename = "ok")]
+    #[default]
     Ok,
-    #[serde(rename = "access_token_invalid")]
     AccessTokenInvalid,
-    #[serde(rename = "internal_error")]
     InternalError,
-    #[serde(rename = "invalid_file_upload")]
     InvalidFileUpload,
-    #[serde(rename = "invalid_params")]
     InvalidParams,
-    #[serde(rename = "rate_limit_exceeded")]
     RateLimitExceeded,
-    #[serde(rename = "scope_not_authorized")]
     ScopeNotAuthorized,
-    #[serde(rename = "scope_permission_missed")]
     ScopePermissionMissed,
+    SpamRiskTooManyPosts,
+    SpamRiskUserBannedFromPosting,
+    ReachedActiveUserCap,
+    UnauditedClientCanOnlyPostToPrivateAccounts,
+    UrlOwnershipUnverified,
+    PrivacyLevelOptionMismatch,
+    TokenNotAuthorizedForSpecifiedDeeplink,
+    Unknown(String),
 }
 
 impl std::fmt::Display for Code {
@@ -54,13 +55,59 @@ impl std::fmt::Display for Code {
             Self::RateLimitExceeded => write!(f, "rate_limit_exceeded"),
             Self::ScopeNotAuthorized => write!(f, "scope_not_authorized"),
             Self::ScopePermissionMissed => write!(f, "scope_permission_missed"),
+            Self::SpamRiskTooManyPosts => write!(f, "spam_risk_too_many_posts"),
+            Self::SpamRiskUserBannedFromPosting => write!(f, "spam_risk_user_banned_from_posting"),
+            Self::ReachedActiveUserCap => write!(f, "reached_active_user_cap"),
+            Self::UnauditedClientCanOnlyPostToPrivateAccounts => {
+                write!(f, "unaudited_client_can_only_post_to_private_accounts")
+            }
+            Self::UrlOwnershipUnverified => write!(f, "url_ownership_unverified"),
+            Self::PrivacyLevelOptionMismatch => write!(f, "privacy_level_option_mismatch"),
+            Self::TokenNotAuthorizedForSpecifiedDeeplink => {
+                write!(f, "token_not_authorized_for_specified_deeplink")
+            }
+            Self::Unknown(value) => write!(f, "{}", value),
         }
     }
 }
 
-impl Default for Code {
-    fn default() -> Self {
-        Self::Ok
+impl From<&str> for Code {
+    fn from(value: &str) -> Self {
+        match value {
+            "ok" => Self::Ok,
+            "access_token_invalid" => Self::AccessTokenInvalid,
+            "internal_error" => Self::InternalError,
+            "invalid_file_upload" => Self::InvalidFileUpload,
+            "invalid_params" => Self::InvalidParams,
+            "rate_limit_exceeded" => Self::RateLimitExceeded,
+            "scope_not_authorized" => Self::ScopeNotAuthorized,
+            "scope_permission_missed" => Self::ScopePermissionMissed,
+            "spam_risk_too_many_posts" => Self::SpamRiskTooManyPosts,
+            "spam_risk_user_banned_from_posting" => Self::SpamRiskUserBannedFromPosting,
+            "reached_active_user_cap" => Self::ReachedActiveUserCap,
+            "unaudited_client_can_only_post_to_private_accounts" => {
+                Self::UnauditedClientCanOnlyPostToPrivateAccounts
+            }
+            "url_ownership_unverified" => Self::UrlOwnershipUnverified,
+            "privacy_level_option_mismatch" => Self::PrivacyLevelOptionMismatch,
+            "token_not_authorized_for_specified_deeplink" => {
+                Self::TokenNotAuthorizedForSpecifiedDeeplink
+            }
+            _ => Self::Unknown(value.to_owned()),
+        }
+    }
+}
+
+impl Serialize for Code {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Code {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(Self::from(value.as_str()))
     }
 }
 
@@ -0,0 +1,20 @@
+use serde::{de, Deserialize, Deserializer};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Integer(i64),
+    Text(String),
+}
+
+// Counters arrive as JSON numbers, but some responses quote them.
+pub(crate) fn option_i64<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Number>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Number::Integer(value)) => Ok(Some(value)),
+        Some(Number::Text(value)) => value.trim().parse().map(Some).map_err(de::Error::custom),
+    }
+}
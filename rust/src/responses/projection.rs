@@ -0,0 +1,100 @@
+use crate::{
+    error::Error,
+    responses::{
+        user::{User, UserField},
+        video::{Video, VideoField},
+    },
+};
+use std::{collections::HashSet, hash::Hash};
+
+// A response model paired with the field set it was requested with. Accessors
+// return the value of a requested field, or an error when the field was not
+// requested or TikTok omitted it.
+#[derive(Debug, Clone)]
+pub struct Projected<T, F> {
+    value: T,
+    fields: HashSet<F>,
+}
+
+pub type UserView = Projected<User, UserField>;
+pub type VideoView = Projected<Video, VideoField>;
+
+impl<T, F: Eq + Hash + std::fmt::Display> Projected<T, F> {
+    pub fn new(value: T, fields: HashSet<F>) -> Self {
+        Self { value, fields }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    pub fn fields(&self) -> &HashSet<F> {
+        &self.fields
+    }
+
+    fn get<'a, V>(&self, field: F, value: Option<&'a V>) -> Result<&'a V, Error> {
+        if !self.fields.contains(&field) {
+            return Err(Error::FieldNotRequested(field.to_string()));
+        }
+        value.ok_or_else(|| Error::MissingField(field.to_string()))
+    }
+}
+
+macro_rules! accessors {
+    ($model:ty, $field:ident, { $($name:ident: $variant:ident -> $ret:ty,)* }) => {
+        impl Projected<$model, $field> {
+            $(
+                pub fn $name(&self) -> Result<$ret, Error> {
+                    self.get($field::$variant, self.value.$name.as_ref())
+                        .map(|it| it.to_owned())
+                }
+            )*
+        }
+    };
+}
+
+accessors!(User, UserField, {
+    open_id: OpenId -> String,
+    union_id: UnionId -> String,
+    avatar_url: AvatarUrl -> String,
+    avatar_url_100: AvatarUrl100 -> String,
+    avatar_large_url: AvatarLargeUrl -> String,
+    display_name: DisplayName -> String,
+    bio_description: BioDescription -> String,
+    profile_deep_link: ProfileDeepLink -> String,
+    is_verified: IsVerified -> bool,
+    username: Username -> String,
+    follower_count: FollowerCount -> i64,
+    following_count: FollowingCount -> i64,
+    likes_count: LikesCount -> i64,
+    video_count: VideoCount -> i64,
+});
+
+accessors!(Video, VideoField, {
+    id: Id -> String,
+    create_time: CreateTime -> i64,
+    cover_image_url: CoverImageUrl -> String,
+    share_url: ShareUrl -> String,
+    video_description: VideoDescription -> String,
+    duration: Duration -> i32,
+    height: Height -> i32,
+    width: Width -> i32,
+    title: Title -> String,
+    embed_html: EmbedHtml -> String,
+    embed_link: EmbedLink -> String,
+    like_count: LikeCount -> i64,
+    comment_count: CommentCount -> i64,
+    share_count: ShareCount -> i64,
+    view_count: ViewCount -> i64,
+});
+
+pub fn project_videos(videos: Vec<Video>, fields: &HashSet<VideoField>) -> Vec<VideoView> {
+    videos
+        .into_iter()
+        .map(|it| Projected::new(it, fields.clone()))
+        .collect()
+}
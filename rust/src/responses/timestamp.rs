@@ -0,0 +1,32 @@
+use crate::responses::video::Video;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// TikTok sends create_time as Unix seconds in UTC and duration in seconds.
+// created_at returns std types; the _chrono and _time variants return the
+// types of those crates.
+impl Video {
+    pub fn created_at(&self) -> Option<SystemTime> {
+        let seconds = self.create_time?;
+        let offset = Duration::from_secs(seconds.unsigned_abs());
+        if seconds < 0 {
+            UNIX_EPOCH.checked_sub(offset)
+        } else {
+            UNIX_EPOCH.checked_add(offset)
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn created_at_chrono(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::from_timestamp(self.create_time?, 0)
+    }
+
+    #[cfg(feature = "time")]
+    pub fn created_at_time(&self) -> Option<time::OffsetDateTime> {
+        time::OffsetDateTime::from_unix_timestamp(self.create_time?).ok()
+    }
+
+    pub fn length(&self) -> Option<Duration> {
+        let seconds = u64::try_from(self.duration?).ok()?;
+        Some(Duration::from_secs(seconds))
+    }
+}
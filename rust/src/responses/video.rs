@@ -26,13 +26,17 @@ pub struct Video {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub embed_link: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub like_count: Option<i32>,
+    #[serde(default, deserialize_with = "crate::responses::number::option_i64")]
+    pub like_count: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub comment_count: Option<i32>,
+    #[serde(default, deserialize_with = "crate::responses::number::option_i64")]
+    pub comment_count: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub share_count: Option<i32>,
+    #[serde(default, deserialize_with = "crate::responses::number::option_i64")]
+    pub share_count: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub view_count: Option<i32>,
+    #[serde(default, deserialize_with = "crate::responses::number::option_i64")]
+    pub view_count: Option<i64>,
     #[serde(flatten)]
     pub extra: std::collections::HashMap<String, serde_json::Value>,
 }
@@ -0,0 +1,60 @@
+use crate::error::Error;
+use rand::Rng;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: bool,
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    // Backoff before the retry following the given attempt (1 based), doubling
+    // each time up to max_backoff. With jitter a random duration up to that
+    // value is used instead.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(rand::thread_rng().gen::<f64>())
+        } else {
+            backoff
+        }
+    }
+
+    pub fn is_retryable(&self, err: &Error) -> bool {
+        err.is_retryable()
+    }
+
+    // Decides whether to retry after the given attempt (1 based) failed with
+    // err, `elapsed` after the first attempt started. Returns the backoff to
+    // wait, or None when the error should be returned.
+    pub fn retry_after(&self, attempt: u32, elapsed: Duration, err: &Error) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.is_retryable(err) {
+            return None;
+        }
+        let backoff = self.backoff(attempt);
+        match self.deadline {
+            Some(deadline) if elapsed + backoff >= deadline => None,
+            _ => Some(backoff),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    apis::{execute_api, ApiResponse},
+    error::Error,
+    options::{make_transport, TiktokOptions},
+    transport::HttpRequest,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    future::{poll_fn, Future},
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower::Service;
+
+// The innermost service of the pipeline. It sends the request with the same
+// handling as `execute_api` and deserializes the body into T.
+pub struct ApiService<T> {
+    options: Option<TiktokOptions>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ApiService<T> {
+    pub fn new(options: Option<TiktokOptions>) -> Self {
+        let transport = make_transport(&options);
+        let mut options = options.unwrap_or_default();
+        options.transport = Some(transport);
+        Self {
+            options: Some(options),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for ApiService<T> {
+    fn clone(&self) -> Self {
+        Self {
+            options: self.options.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for ApiService<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiService")
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<T> Service<HttpRequest> for ApiService<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    type Response = ApiResponse<T>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<ApiResponse<T>, Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let options = self.options.clone();
+        Box::pin(async move { execute_api(request, &options).await })
+    }
+}
+
+// Sends a request built by an `Api::build` through the service stack.
+pub async fn call<S, T>(service: &mut S, request: HttpRequest) -> Result<T, S::Error>
+where
+    S: Service<HttpRequest, Response = T>,
+{
+    poll_fn(|cx| service.poll_ready(cx)).await?;
+    service.call(request).await
+}
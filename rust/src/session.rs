@@ -0,0 +1,247 @@
+use crate::{
+    client::{CredentialSource, TiktokClient},
+    error::Error,
+    oauth::{RevokeResult, TiktokOauth, TokenResult},
+    options::{make_transport, TiktokOptions},
+    token_store::TokenStore,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountStatus {
+    Active,
+    NeedsReauth,
+}
+
+#[derive(Debug, Clone)]
+struct AccountState {
+    status: AccountStatus,
+    expires_at: Option<Instant>,
+}
+
+impl AccountState {
+    fn active(token: &TokenResult) -> Self {
+        Self {
+            status: AccountStatus::Active,
+            expires_at: Some(Instant::now() + Duration::from_secs(token.expires_in)),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at
+            .map(|it| it <= Instant::now() + REFRESH_MARGIN)
+            .unwrap_or(false)
+    }
+}
+
+// Account status is kept in memory only. After a restart every stored account
+// is treated as active until a refresh fails again.
+pub struct SessionManager<S> {
+    oauth: TiktokOauth,
+    store: S,
+    options: Option<TiktokOptions>,
+    accounts: Mutex<HashMap<String, AccountState>>,
+    refresh_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl<S: TokenStore + Sync> SessionManager<S> {
+    pub fn new(oauth: TiktokOauth, store: S, options: Option<TiktokOptions>) -> Self {
+        let transport = make_transport(&options);
+        let mut options = options.unwrap_or_default();
+        options.transport = Some(transport);
+        Self {
+            oauth,
+            store,
+            options: Some(options),
+            accounts: Mutex::new(HashMap::new()),
+            refresh_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn oauth(&self) -> &TiktokOauth {
+        &self.oauth
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    fn accounts(&self) -> MutexGuard<'_, HashMap<String, AccountState>> {
+        self.accounts.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn set_state(&self, open_id: &str, state: AccountState) {
+        self.accounts().insert(open_id.to_owned(), state);
+    }
+
+    pub async fn authorize(&self, code: &str) -> Result<TokenResult, Error> {
+        let token = self.oauth.token(code).await?;
+        self.add(&token).await?;
+        Ok(token)
+    }
+
+    pub async fn add(&self, token: &TokenResult) -> Result<(), Error> {
+        self.store.save(token).await?;
+        self.set_state(&token.open_id, AccountState::active(token));
+        Ok(())
+    }
+
+    pub fn for_account(&self, open_id: &str) -> TiktokClient<AccountHandle<'_, S>> {
+        TiktokClient::new(
+            AccountHandle {
+                manager: self,
+                open_id: open_id.to_owned(),
+            },
+            self.options.clone(),
+        )
+    }
+
+    pub async fn open_ids(&self) -> Result<Vec<String>, Error> {
+        self.store.open_ids().await
+    }
+
+    pub fn status(&self, open_id: &str) -> Option<AccountStatus> {
+        self.accounts().get(open_id).map(|it| it.status)
+    }
+
+    pub fn needs_reauth(&self) -> Vec<String> {
+        self.accounts()
+            .iter()
+            .filter(|(_, state)| state.status == AccountStatus::NeedsReauth)
+            .map(|(open_id, _)| open_id.clone())
+            .collect()
+    }
+
+    pub fn mark_needs_reauth(&self, open_id: &str) {
+        self.set_state(
+            open_id,
+            AccountState {
+                status: AccountStatus::NeedsReauth,
+                expires_at: None,
+            },
+        );
+    }
+
+    fn refresh_lock(&self, open_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.refresh_locks
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(open_id.to_owned())
+            .or_default()
+            .clone()
+    }
+
+    pub async fn refresh(&self, open_id: &str) -> Result<TokenResult, Error> {
+        let stale = self.store.load(open_id).await?.map(|it| it.access_token);
+        self.refresh_stale(open_id, stale.as_deref()).await
+    }
+
+    // Refreshes run one at a time per account. When the stored access token
+    // no longer matches the stale one, another caller already refreshed and
+    // its token is returned instead of spending the rotated refresh token
+    // a second time.
+    async fn refresh_stale(
+        &self,
+        open_id: &str,
+        stale: Option<&str>,
+    ) -> Result<TokenResult, Error> {
+        let lock = self.refresh_lock(open_id);
+        let _guard = lock.lock().await;
+        let Some(current) = self.store.load(open_id).await? else {
+            self.mark_needs_reauth(open_id);
+            return Err(Error::ReauthorizationRequired(open_id.to_owned()));
+        };
+        if stale.is_some_and(|it| it != current.access_token) {
+            return Ok(current);
+        }
+        if self.status(open_id) == Some(AccountStatus::NeedsReauth) {
+            return Err(Error::ReauthorizationRequired(open_id.to_owned()));
+        }
+        match self.oauth.refresh(&current.refresh_token).await {
+            Ok(token) => {
+                self.add(&token).await?;
+                Ok(token)
+            }
+            Err(Error::OAuth(err, status_code)) => {
+                if err.requires_reauthorization() {
+                    self.mark_needs_reauth(open_id);
+                }
+                Err(Error::OAuth(err, status_code))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn revoke(&self, open_id: &str) -> Result<RevokeResult, Error> {
+        let res = match self.store.load(open_id).await? {
+            Some(token) => self.oauth.revoke(&token.access_token).await?,
+            None => RevokeResult::AlreadyInvalid,
+        };
+        self.store.remove(open_id).await?;
+        self.accounts().remove(open_id);
+        self.refresh_locks
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(open_id);
+        Ok(res)
+    }
+
+    async fn access_token(&self, open_id: &str) -> Result<String, Error> {
+        let state = self.accounts().get(open_id).cloned();
+        if state
+            .as_ref()
+            .is_some_and(|it| it.status == AccountStatus::NeedsReauth)
+        {
+            return Err(Error::ReauthorizationRequired(open_id.to_owned()));
+        }
+        let Some(token) = self.store.load(open_id).await? else {
+            return Err(Error::ReauthorizationRequired(open_id.to_owned()));
+        };
+        if state.is_some_and(|it| it.is_expired()) {
+            return Ok(self
+                .refresh_stale(open_id, Some(&token.access_token))
+                .await?
+                .access_token);
+        }
+        Ok(token.access_token)
+    }
+}
+
+pub struct AccountHandle<'a, S> {
+    manager: &'a SessionManager<S>,
+    open_id: String,
+}
+
+impl<'a, S: TokenStore + Sync> AccountHandle<'a, S> {
+    pub fn open_id(&self) -> &str {
+        &self.open_id
+    }
+
+    pub fn status(&self) -> Option<AccountStatus> {
+        self.manager.status(&self.open_id)
+    }
+}
+
+impl<'a, S: TokenStore + Sync> CredentialSource for AccountHandle<'a, S> {
+    async fn access_token(&self) -> Result<String, Error> {
+        self.manager.access_token(&self.open_id).await
+    }
+
+    async fn refresh_access_token(&self, rejected: &str) -> Result<Option<String>, Error> {
+        let token = self
+            .manager
+            .refresh_stale(&self.open_id, Some(rejected))
+            .await?;
+        Ok(Some(token.access_token))
+    }
+
+    async fn invalidate(&self) {
+        self.manager.mark_needs_reauth(&self.open_id);
+    }
+}
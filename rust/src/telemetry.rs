@@ -0,0 +1,119 @@
+use crate::{
+    error::Error,
+    metrics::{CallRecord, MetricsObserver},
+    options::TiktokOptions,
+    transport::{HttpRequest, HttpResponse},
+};
+use http::{Method, StatusCode};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+// Per call instrumentation. Only the endpoint path and method are taken from
+// the request, so bearer tokens, query strings and form bodies with client
+// secrets never reach the span or the metrics observer.
+pub(crate) struct Telemetry {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    endpoint: String,
+    method: Method,
+    status: AtomicU16,
+    observer: Option<Arc<dyn MetricsObserver>>,
+    started: Instant,
+}
+
+impl Telemetry {
+    pub(crate) fn new(request: &HttpRequest, options: &Option<TiktokOptions>) -> Self {
+        let endpoint = request.path();
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "tiktok_api",
+                endpoint = %endpoint,
+                method = %request.method,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                log_id = tracing::field::Empty,
+                retries = tracing::field::Empty,
+            ),
+            endpoint,
+            method: request.method.clone(),
+            status: AtomicU16::new(0),
+            observer: options.as_ref().and_then(|it| it.metrics.clone()),
+            started: Instant::now(),
+        }
+    }
+
+    // Runs the request inside the span so transport, retry and user events
+    // are attributed to the call.
+    pub(crate) async fn instrument<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tracing")]
+        return tracing::Instrument::instrument(future, self.span.clone()).await;
+        #[cfg(not(feature = "tracing"))]
+        future.await
+    }
+
+    #[cfg(feature = "blocking")]
+    pub(crate) fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "tracing")]
+        return self.span.in_scope(f);
+        #[cfg(not(feature = "tracing"))]
+        f()
+    }
+
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn response(&self, response: &HttpResponse, json: Option<&serde_json::Value>) {
+        self.status
+            .store(response.status.as_u16(), Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("status", response.status.as_u16());
+            if let Some(log_id) = response.log_id_in(json) {
+                self.span.record("log_id", log_id.as_str());
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn quota_failed(&self, err: &Error) {
+        #[cfg(feature = "tracing")]
+        self.span
+            .in_scope(|| tracing::warn!(error = %err, "research quota record failed"));
+    }
+
+    pub(crate) fn finish<T>(&self, retries: u32, result: &Result<T, Error>) {
+        let latency = self.started.elapsed();
+        #[cfg(feature = "tracing")]
+        self.trace(latency, retries, result);
+        if let Some(observer) = self.observer.as_ref() {
+            observer.record(&CallRecord {
+                endpoint: &self.endpoint,
+                method: &self.method,
+                status: StatusCode::from_u16(self.status.load(Ordering::Relaxed)).ok(),
+                latency,
+                retries,
+                error: result.as_ref().err(),
+            });
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    fn trace<T>(&self, latency: std::time::Duration, retries: u32, result: &Result<T, Error>) {
+        self.span.record("latency_ms", latency.as_millis() as u64);
+        self.span.record("retries", retries);
+        if let Err(Error::Api(err, _)) = result {
+            if let Some(log_id) = err.log_id.as_ref() {
+                self.span.record("log_id", log_id.as_str());
+            }
+        }
+        self.span.in_scope(|| match result {
+            Ok(_) => tracing::debug!("tiktok api succeeded"),
+            Err(err) => tracing::warn!(error = %err, "tiktok api failed"),
+        });
+    }
+}
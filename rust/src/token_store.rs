@@ -0,0 +1,55 @@
+use crate::{error::Error, oauth::TokenResult};
+use std::{collections::HashMap, future::Future, sync::Mutex};
+
+#[cfg(feature = "encryption")]
+mod encrypted;
+#[cfg(feature = "encryption")]
+pub use encrypted::{EncryptedTokenStore, TokenCipher};
+
+pub trait TokenStore {
+    fn load(
+        &self,
+        open_id: &str,
+    ) -> impl Future<Output = Result<Option<TokenResult>, Error>> + Send;
+
+    fn save(&self, token: &TokenResult) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn remove(&self, open_id: &str) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn open_ids(&self) -> impl Future<Output = Result<Vec<String>, Error>> + Send;
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    tokens: Mutex<HashMap<String, TokenResult>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, TokenResult>> {
+        self.tokens.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    async fn load(&self, open_id: &str) -> Result<Option<TokenResult>, Error> {
+        Ok(self.lock().get(open_id).cloned())
+    }
+
+    async fn save(&self, token: &TokenResult) -> Result<(), Error> {
+        self.lock().insert(token.open_id.clone(), token.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, open_id: &str) -> Result<(), Error> {
+        self.lock().remove(open_id);
+        Ok(())
+    }
+
+    async fn open_ids(&self) -> Result<Vec<String>, Error> {
+        Ok(self.lock().keys().cloned().collect())
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{error::Error, oauth::TokenResult, token_store::TokenStore};
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::prelude::{Engine as _, BASE64_URL_SAFE_NO_PAD};
+use rand::Rng;
+use std::{
+    collections::HashMap,
+    sync::{RwLock, RwLockReadGuard},
+};
+
+const NONCE_SIZE: usize = 12;
+const ACCESS_TOKEN: &str = "access_token";
+const REFRESH_TOKEN: &str = "refresh_token";
+
+// Sealed values are stored as "{key_id}:{base64(nonce || ciphertext)}" so that
+// values written with a retired key can still be opened after rotation.
+#[derive(Clone)]
+pub struct TokenCipher {
+    current_key_id: String,
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl TokenCipher {
+    pub fn new(key_id: &str, key: &[u8; 32]) -> Result<Self, Error> {
+        let mut keys = HashMap::new();
+        keys.insert(check_key_id(key_id)?, Aes256Gcm::new(key.into()));
+        Ok(Self {
+            current_key_id: key_id.to_owned(),
+            keys,
+        })
+    }
+
+    // Registers a key that is only used to open values sealed before a rotation.
+    pub fn add_decryption_key(mut self, key_id: &str, key: &[u8; 32]) -> Result<Self, Error> {
+        self.keys
+            .insert(check_key_id(key_id)?, Aes256Gcm::new(key.into()));
+        Ok(self)
+    }
+
+    // Makes the given key current. The previous keys remain available for opening.
+    pub fn rotate(&mut self, key_id: &str, key: &[u8; 32]) -> Result<(), Error> {
+        self.keys
+            .insert(check_key_id(key_id)?, Aes256Gcm::new(key.into()));
+        self.current_key_id = key_id.to_owned();
+        Ok(())
+    }
+
+    pub fn remove_key(&mut self, key_id: &str) -> Result<(), Error> {
+        if key_id == self.current_key_id {
+            return Err(Error::TokenStore(format!(
+                "cannot remove current key {}",
+                key_id
+            )));
+        }
+        self.keys.remove(key_id);
+        Ok(())
+    }
+
+    pub fn current_key_id(&self) -> &str {
+        &self.current_key_id
+    }
+
+    pub fn seal(&self, plaintext: &str, aad: &str) -> Result<String, Error> {
+        let cipher = &self.keys[&self.current_key_id];
+        let nonce_bytes: [u8; NONCE_SIZE] = rand::thread_rng().gen();
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce_bytes),
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| Error::TokenStore("encrypt failed".to_owned()))?;
+        let mut sealed = nonce_bytes.to_vec();
+        sealed.extend(ciphertext);
+        Ok(format!(
+            "{}:{}",
+            self.current_key_id,
+            BASE64_URL_SAFE_NO_PAD.encode(sealed)
+        ))
+    }
+
+    pub fn open(&self, sealed: &str, aad: &str) -> Result<String, Error> {
+        let (key_id, payload) = split_sealed(sealed)?;
+        let Some(cipher) = self.keys.get(key_id) else {
+            return Err(Error::TokenStore(format!("unknown key id {}", key_id)));
+        };
+        let bytes = BASE64_URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|err| Error::TokenStore(err.to_string()))?;
+        if bytes.len() < NONCE_SIZE {
+            return Err(Error::TokenStore("sealed value too short".to_owned()));
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_SIZE);
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| Error::TokenStore("decrypt failed".to_owned()))?;
+        String::from_utf8(plaintext).map_err(|err| Error::TokenStore(err.to_string()))
+    }
+
+    pub fn is_current(&self, sealed: &str) -> bool {
+        split_sealed(sealed)
+            .map(|(key_id, _)| key_id == self.current_key_id)
+            .unwrap_or(false)
+    }
+}
+
+impl std::fmt::Debug for TokenCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenCipher")
+            .field("current_key_id", &self.current_key_id)
+            .field("key_ids", &self.keys.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn check_key_id(key_id: &str) -> Result<String, Error> {
+    if key_id.is_empty() || key_id.contains(':') {
+        return Err(Error::TokenStore(format!("invalid key id {}", key_id)));
+    }
+    Ok(key_id.to_owned())
+}
+
+fn split_sealed(sealed: &str) -> Result<(&str, &str), Error> {
+    sealed
+        .split_once(':')
+        .ok_or_else(|| Error::TokenStore("malformed sealed value".to_owned()))
+}
+
+fn aad(open_id: &str, field: &str) -> String {
+    format!("{}:{}", open_id, field)
+}
+
+#[derive(Debug)]
+pub struct EncryptedTokenStore<S> {
+    inner: S,
+    cipher: RwLock<TokenCipher>,
+}
+
+impl<S: TokenStore + Sync> EncryptedTokenStore<S> {
+    pub fn new(inner: S, cipher: TokenCipher) -> Self {
+        Self {
+            inner,
+            cipher: RwLock::new(cipher),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn cipher(&self) -> TokenCipher {
+        self.read_cipher().clone()
+    }
+
+    pub fn cipher_mut(&mut self) -> &mut TokenCipher {
+        self.cipher.get_mut().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Makes the given key current on a live store. Follow with reencrypt_all to
+    // move stored tokens to the new key.
+    pub fn rotate(&self, key_id: &str, key: &[u8; 32]) -> Result<(), Error> {
+        self.cipher
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .rotate(key_id, key)
+    }
+
+    fn read_cipher(&self) -> RwLockReadGuard<'_, TokenCipher> {
+        self.cipher.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn seal(&self, token: &TokenResult) -> Result<TokenResult, Error> {
+        let cipher = self.read_cipher();
+        let mut sealed = token.clone();
+        sealed.access_token =
+            cipher.seal(&token.access_token, &aad(&token.open_id, ACCESS_TOKEN))?;
+        sealed.refresh_token =
+            cipher.seal(&token.refresh_token, &aad(&token.open_id, REFRESH_TOKEN))?;
+        Ok(sealed)
+    }
+
+    fn open(&self, sealed: TokenResult) -> Result<TokenResult, Error> {
+        let cipher = self.read_cipher();
+        let mut token = sealed;
+        token.access_token =
+            cipher.open(&token.access_token, &aad(&token.open_id, ACCESS_TOKEN))?;
+        token.refresh_token =
+            cipher.open(&token.refresh_token, &aad(&token.open_id, REFRESH_TOKEN))?;
+        Ok(token)
+    }
+
+    // Re-encrypts a stored token with the current key. Returns false when the
+    // token was missing or already sealed with the current key.
+    pub async fn reencrypt(&self, open_id: &str) -> Result<bool, Error> {
+        let Some(sealed) = self.inner.load(open_id).await? else {
+            return Ok(false);
+        };
+        let is_current = {
+            let cipher = self.read_cipher();
+            cipher.is_current(&sealed.access_token) && cipher.is_current(&sealed.refresh_token)
+        };
+        if is_current {
+            return Ok(false);
+        }
+        let token = self.open(sealed)?;
+        self.inner.save(&self.seal(&token)?).await?;
+        Ok(true)
+    }
+
+    pub async fn reencrypt_all(&self) -> Result<usize, Error> {
+        let mut count = 0;
+        for open_id in self.inner.open_ids().await? {
+            if self.reencrypt(&open_id).await? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl<S: TokenStore + Sync> TokenStore for EncryptedTokenStore<S> {
+    async fn load(&self, open_id: &str) -> Result<Option<TokenResult>, Error> {
+        match self.inner.load(open_id).await? {
+            Some(sealed) => Ok(Some(self.open(sealed)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn save(&self, token: &TokenResult) -> Result<(), Error> {
+        let sealed = self.seal(token)?;
+        self.inner.save(&sealed).await
+    }
+
+    async fn remove(&self, open_id: &str) -> Result<(), Error> {
+        self.inner.remove(open_id).await
+    }
+
+    async fn open_ids(&self) -> Result<Vec<String>, Error> {
+        self.inner.open_ids().await
+    }
+}
@@ -0,0 +1,337 @@
+use crate::error::Error;
+use http::{
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    HeaderMap, HeaderName, HeaderValue, Method, StatusCode,
+};
+use serde::Serialize;
+use std::{collections::VecDeque, future::Future, pin::Pin, sync::Mutex, time::Duration};
+use url::form_urlencoded;
+
+pub const LOG_ID: &str = "x-tt-logid";
+
+#[derive(Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: &str) -> Self {
+        Self {
+            method,
+            url: url.to_owned(),
+            headers: HeaderMap::new(),
+            body: vec![],
+            timeout: None,
+        }
+    }
+
+    pub fn get(url: &str) -> Self {
+        Self::new(Method::GET, url)
+    }
+
+    pub fn post(url: &str) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    pub fn path(&self) -> String {
+        url::Url::parse(&self.url)
+            .map(|it| it.path().to_owned())
+            .unwrap_or_default()
+    }
+
+    pub fn query<K: AsRef<str>, V: AsRef<str>>(mut self, parameters: &[(K, V)]) -> Self {
+        let query = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(parameters)
+            .finish();
+        if !query.is_empty() {
+            self.url
+                .push(if self.url.contains('?') { '&' } else { '?' });
+            self.url.push_str(&query);
+        }
+        self
+    }
+
+    pub fn header(mut self, name: HeaderName, value: &str) -> Result<Self, Error> {
+        let value =
+            HeaderValue::from_str(value).map_err(|err| Error::Transport(err.to_string()))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    pub fn bearer_auth(self, bearer_code: &str) -> Result<Self, Error> {
+        self.header(AUTHORIZATION, &format!("Bearer {}", bearer_code))
+    }
+
+    pub fn json<T: Serialize>(mut self, body: &T) -> Result<Self, Error> {
+        self.body = serde_json::to_vec(body)?;
+        self.header(CONTENT_TYPE, "application/json")
+    }
+
+    pub fn form<K: AsRef<str>, V: AsRef<str>>(
+        mut self,
+        form: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self, Error> {
+        self.body = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form)
+            .finish()
+            .into_bytes();
+        self.header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl std::fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut headers = self.headers.clone();
+        if headers.contains_key(AUTHORIZATION) {
+            headers.insert(AUTHORIZATION, HeaderValue::from_static("[REDACTED]"));
+        }
+        // Form bodies carry the client secret and tokens.
+        f.debug_struct("HttpRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &headers)
+            .field("body", &format_args!("[{} bytes]", self.body.len()))
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, body: &str) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    // TikTok returns the log id in the x-tt-logid header and in error.log_id.
+    pub fn log_id(&self) -> Option<String> {
+        self.log_id_in(None)
+    }
+
+    pub(crate) fn header_log_id(&self) -> Option<String> {
+        self.headers
+            .get(LOG_ID)
+            .and_then(|it| it.to_str().ok())
+            .filter(|it| !it.is_empty())
+            .map(|it| it.to_owned())
+    }
+
+    // Same as log_id, reading error.log_id from an already parsed body.
+    pub(crate) fn log_id_in(&self, json: Option<&serde_json::Value>) -> Option<String> {
+        if let Some(log_id) = self.header_log_id() {
+            return Some(log_id);
+        }
+        let parsed;
+        let json = match json {
+            Some(json) => json,
+            None => {
+                parsed = serde_json::from_slice::<serde_json::Value>(&self.body).ok()?;
+                &parsed
+            }
+        };
+        json["error"]["log_id"]
+            .as_str()
+            .filter(|it| !it.is_empty())
+            .map(|it| it.to_owned())
+    }
+}
+
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<HttpResponse, Error>> + Send + 'a>>;
+
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut builder = self
+                .client
+                .request(request.method, request.url)
+                .headers(request.headers)
+                .body(request.body);
+            if let Some(timeout) = request.timeout {
+                builder = builder.timeout(timeout);
+            }
+            let response = builder.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+#[cfg(feature = "blocking")]
+pub trait BlockingTransport: std::fmt::Debug + Send + Sync {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error>;
+}
+
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+#[derive(Debug, Clone)]
+pub struct BlockingReqwestTransport {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+impl BlockingReqwestTransport {
+    pub fn new(client: reqwest::blocking::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+impl BlockingTransport for BlockingReqwestTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        let mut builder = self
+            .client
+            .request(request.method, request.url)
+            .headers(request.headers)
+            .body(request.body);
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let response = builder.send()?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes()?.to_vec();
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+// Used when the reqwest feature is off and no transport is configured.
+#[cfg(not(feature = "reqwest"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct MissingTransport;
+
+#[cfg(not(feature = "reqwest"))]
+impl MissingTransport {
+    fn error() -> Error {
+        Error::Transport(
+            "no transport configured; set TiktokOptions::transport or enable the reqwest feature"
+                .to_owned(),
+        )
+    }
+}
+
+#[cfg(not(feature = "reqwest"))]
+impl Transport for MissingTransport {
+    fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async { Err(Self::error()) })
+    }
+}
+
+#[cfg(all(feature = "blocking", not(feature = "reqwest")))]
+impl BlockingTransport for MissingTransport {
+    fn send(&self, _request: HttpRequest) -> Result<HttpResponse, Error> {
+        Err(Self::error())
+    }
+}
+
+// Returns queued responses in order and records every request, for tests.
+#[derive(Debug, Default)]
+pub struct MemoryTransport {
+    responses: Mutex<VecDeque<HttpResponse>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_response(&self, response: HttpResponse) {
+        self.responses
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push_back(response);
+    }
+
+    pub fn push_json(&self, status: StatusCode, body: &str) {
+        let mut response = HttpResponse::new(status, body);
+        response
+            .headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        self.push_response(response);
+    }
+
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
+
+impl MemoryTransport {
+    fn respond(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(request);
+        self.responses
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .pop_front()
+            .ok_or_else(|| Error::Transport("no response queued".to_owned()))
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        let response = self.respond(request);
+        Box::pin(async move { response })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingTransport for MemoryTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        self.respond(request)
+    }
+}
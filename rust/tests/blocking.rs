@@ -0,0 +1,106 @@
+#![cfg(feature = "blocking")]
+
+use http::StatusCode;
+use std::{sync::Arc, time::Duration};
+use tiktokapi_v2::{
+    apis::get_v2_user_info::Api,
+    error::Error,
+    oauth::{RevokeResult, TiktokOauth},
+    options::TiktokOptions,
+    quota::{MemoryQuotaStore, QuotaMode, QuotaUsage, ResearchQuota},
+    responses::user::UserField,
+    retry::RetryPolicy,
+    transport::{HttpRequest, MemoryTransport},
+};
+
+fn options(transport: &Arc<MemoryTransport>) -> TiktokOptions {
+    TiktokOptions {
+        blocking_transport: Some(transport.clone()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn execute_blocking_retries_and_parses() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(StatusCode::BAD_GATEWAY, "{}");
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"user":{"open_id":"o1"}},"error":{"code":"ok","log_id":"l1"}}"#,
+    );
+    let options = TiktokOptions {
+        retry: Some(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            jitter: false,
+            ..Default::default()
+        }),
+        ..options(&transport)
+    };
+
+    let res = Api::new(UserField::all(), Some(options))
+        .execute_blocking("token")
+        .unwrap();
+    assert_eq!(res.log_id.as_deref(), Some("l1"));
+    let user = res.into_inner().data.unwrap().user.unwrap();
+    assert_eq!(user.open_id.as_deref(), Some("o1"));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn execute_blocking_enforces_research_quota() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"1"}]},"error":{"code":"ok"}}"#,
+    );
+    let quota = ResearchQuota::new("app", Arc::new(MemoryQuotaStore::new()))
+        .limit(QuotaUsage::new(1, 100))
+        .mode(QuotaMode::Enforce);
+    let options = Some(TiktokOptions {
+        research_quota: Some(quota),
+        ..options(&transport)
+    });
+    let url = "https://open.tiktokapis.com/v2/research/video/query/";
+
+    tiktokapi_v2::blocking::execute_api::<serde_json::Value>(HttpRequest::post(url), &options)
+        .unwrap();
+    let res =
+        tiktokapi_v2::blocking::execute_api::<serde_json::Value>(HttpRequest::post(url), &options);
+    assert!(matches!(res, Err(Error::QuotaExhausted(_))));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn oauth_blocking_token_and_revoke() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"open_id":"o1","scope":"user.info.basic","access_token":"at","expires_in":86400,"refresh_token":"rt","refresh_expires_in":31536000,"token_type":"Bearer"}"#,
+    );
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"error":"invalid_grant","error_description":"","log_id":"a"}"#,
+    );
+    transport.push_json(
+        StatusCode::BAD_REQUEST,
+        r#"{"error":"invalid_client","error_description":"","log_id":"b"}"#,
+    );
+    let oauth = TiktokOauth::new_with_options(
+        "key",
+        "secret",
+        "http://localhost",
+        vec![],
+        Some(options(&transport)),
+    );
+
+    let token = oauth.token_blocking("code").unwrap();
+    assert_eq!(token.open_id, "o1");
+    assert_eq!(token.access_token, "at");
+    assert_eq!(
+        oauth.revoke_blocking("at").unwrap(),
+        RevokeResult::AlreadyInvalid
+    );
+    let err = oauth.refresh_blocking("rt").unwrap_err();
+    assert!(err.is_client_error());
+    assert_eq!(transport.requests().len(), 3);
+}
@@ -0,0 +1,134 @@
+use futures_util::StreamExt;
+use http::StatusCode;
+use std::sync::Arc;
+use tiktokapi_v2::{
+    client::{Paging, TiktokClient},
+    options::TiktokOptions,
+    responses::video::VideoField,
+    transport::MemoryTransport,
+};
+
+fn client(transport: &Arc<MemoryTransport>) -> TiktokClient<String> {
+    let options = TiktokOptions {
+        transport: Some(transport.clone()),
+        ..Default::default()
+    };
+    TiktokClient::new("token".to_owned(), Some(options))
+}
+
+fn request_bodies(transport: &MemoryTransport) -> Vec<serde_json::Value> {
+    transport
+        .requests()
+        .iter()
+        .map(|it| serde_json::from_slice(&it.body).unwrap())
+        .collect()
+}
+
+async fn video_ids(client: &TiktokClient<String>, paging: Paging) -> Vec<String> {
+    client
+        .video_list_stream(VideoField::all(), paging)
+        .map(|it| it.unwrap().id.unwrap())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn stream_follows_cursor_inside_data() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"1"},{"id":"2"}],"cursor":100,"has_more":true},"error":{"code":"ok"}}"#,
+    );
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"3"}],"cursor":200,"has_more":false},"error":{"code":"ok"}}"#,
+    );
+
+    let ids = video_ids(&client(&transport), Paging::default()).await;
+    assert_eq!(ids, vec!["1", "2", "3"]);
+    let bodies = request_bodies(&transport);
+    assert_eq!(bodies[0].get("cursor"), None);
+    assert_eq!(bodies[1]["cursor"], 100);
+}
+
+#[tokio::test]
+async fn stream_follows_top_level_cursor() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"1"}]},"cursor":100,"has_more":true,"error":{"code":"ok"}}"#,
+    );
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"2"}]},"cursor":200,"has_more":false,"error":{"code":"ok"}}"#,
+    );
+
+    let ids = video_ids(&client(&transport), Paging::default()).await;
+    assert_eq!(ids, vec!["1", "2"]);
+    assert_eq!(request_bodies(&transport)[1]["cursor"], 100);
+}
+
+#[tokio::test]
+async fn stream_stops_at_max_items() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"1"},{"id":"2"}],"cursor":100,"has_more":true},"error":{"code":"ok"}}"#,
+    );
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"3"}],"cursor":200,"has_more":true},"error":{"code":"ok"}}"#,
+    );
+
+    let paging = Paging {
+        page_size: Some(2),
+        max_items: Some(3),
+    };
+    let ids = video_ids(&client(&transport), paging).await;
+    assert_eq!(ids, vec!["1", "2", "3"]);
+    let bodies = request_bodies(&transport);
+    assert_eq!(bodies.len(), 2);
+    assert_eq!(bodies[0]["max_count"], 2);
+    assert_eq!(bodies[1]["max_count"], 1);
+}
+
+#[tokio::test]
+async fn huge_max_items_keeps_the_page_size() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"1"}],"cursor":100,"has_more":false},"error":{"code":"ok"}}"#,
+    );
+
+    let paging = Paging {
+        page_size: None,
+        max_items: Some(usize::MAX),
+    };
+    let ids = video_ids(&client(&transport), paging).await;
+    assert_eq!(ids, vec!["1"]);
+    assert_eq!(request_bodies(&transport)[0]["max_count"], 20);
+}
+
+#[tokio::test]
+async fn query_all_keeps_batches_that_succeeded() {
+    let transport = Arc::new(MemoryTransport::new());
+    let videos: Vec<_> = (0..19)
+        .map(|it| serde_json::json!({ "id": it.to_string() }))
+        .collect();
+    transport.push_json(
+        StatusCode::OK,
+        &serde_json::json!({ "data": { "videos": videos }, "error": { "code": "ok" } }).to_string(),
+    );
+    transport.push_json(StatusCode::BAD_GATEWAY, "bad gateway");
+
+    let ids = (0..25).map(|it| it.to_string()).collect();
+    let res = client(&transport)
+        .video_query_all(VideoField::all(), ids, 1)
+        .await;
+    assert_eq!(res.videos.len(), 19);
+    assert_eq!(res.missing_ids, vec!["19"]);
+    assert!(!res.is_complete());
+    assert_eq!(res.failures.len(), 1);
+    assert_eq!(res.failures[0].ids, vec!["20", "21", "22", "23", "24"]);
+    assert!(res.failures[0].error.is_retryable());
+}
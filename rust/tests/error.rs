@@ -0,0 +1,81 @@
+use http::StatusCode;
+use tiktokapi_v2::{
+    error::{Error, OAuthError, OAuthErrorCode},
+    quota::QuotaUsage,
+    responses::error::{Code, Error as ApiError},
+};
+
+fn api(code: Code, status: StatusCode) -> Error {
+    Error::Api(
+        ApiError {
+            code: Some(code),
+            ..Default::default()
+        },
+        status,
+    )
+}
+
+fn oauth(code: OAuthErrorCode) -> Error {
+    Error::OAuth(
+        OAuthError {
+            error: code,
+            error_description: String::new(),
+            log_id: String::new(),
+        },
+        StatusCode::BAD_REQUEST,
+    )
+}
+
+fn unknown() -> Code {
+    Code::Unknown("new".to_owned())
+}
+
+fn other(status: StatusCode) -> Error {
+    Error::Other(String::new(), status, None)
+}
+
+#[test]
+fn classifiers_agree() {
+    // error, retryable, auth, client, rate limited, reauthorization
+    #[rustfmt::skip]
+    let cases = [
+        (Error::Timeout("t".into()), true, false, false, false, false),
+        (Error::Connection("c".into()), true, false, false, false, false),
+        (other(StatusCode::UNAUTHORIZED), false, true, false, false, false),
+        (other(StatusCode::TOO_MANY_REQUESTS), true, false, false, true, false),
+        (other(StatusCode::BAD_GATEWAY), true, false, false, false, false),
+        (other(StatusCode::BAD_REQUEST), false, false, true, false, false),
+        (api(Code::AccessTokenInvalid, StatusCode::UNAUTHORIZED), false, true, false, false, false),
+        (api(Code::ScopeNotAuthorized, StatusCode::FORBIDDEN), false, true, false, false, true),
+        (api(Code::RateLimitExceeded, StatusCode::TOO_MANY_REQUESTS), true, false, false, true, false),
+        (api(Code::InternalError, StatusCode::INTERNAL_SERVER_ERROR), true, false, false, false, false),
+        (api(Code::InvalidParams, StatusCode::BAD_REQUEST), false, false, true, false, false),
+        (api(Code::InvalidParams, StatusCode::OK), false, false, true, false, false),
+        (api(unknown(), StatusCode::BAD_GATEWAY), true, false, false, false, false),
+        (api(unknown(), StatusCode::BAD_REQUEST), false, false, true, false, false),
+        (oauth(OAuthErrorCode::InvalidGrant), false, true, false, false, true),
+        (oauth(OAuthErrorCode::InvalidClient), false, false, true, false, false),
+        (oauth(OAuthErrorCode::Unknown("new".to_owned())), false, false, false, false, false),
+        (Error::ReauthorizationRequired("o".to_owned()), false, true, false, false, true),
+        (Error::Validation("v".to_owned()), false, false, true, false, false),
+        (Error::QuotaExhausted(QuotaUsage::default()), false, false, false, false, false),
+    ];
+    for (err, retryable, auth, client, rate_limited, reauthorization) in cases {
+        assert_eq!(err.is_retryable(), retryable, "retryable {:?}", err);
+        assert_eq!(err.is_auth_error(), auth, "auth {:?}", err);
+        assert_eq!(err.is_client_error(), client, "client {:?}", err);
+        assert_eq!(
+            err.is_rate_limited(),
+            rate_limited,
+            "rate limited {:?}",
+            err
+        );
+        assert_eq!(
+            err.requires_reauthorization(),
+            reauthorization,
+            "reauthorization {:?}",
+            err
+        );
+        assert!(!(err.is_retryable() && err.is_client_error()), "{:?}", err);
+    }
+}
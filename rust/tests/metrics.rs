@@ -0,0 +1,123 @@
+use http::{Method, StatusCode};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tiktokapi_v2::{
+    apis::execute_api,
+    metrics::{CallRecord, MetricsObserver},
+    oauth::TiktokOauth,
+    options::TiktokOptions,
+    retry::RetryPolicy,
+    transport::{HttpRequest, MemoryTransport},
+};
+
+#[derive(Debug, PartialEq)]
+struct Recorded {
+    endpoint: String,
+    method: Method,
+    status: Option<StatusCode>,
+    retries: u32,
+    code: String,
+}
+
+#[derive(Debug, Default)]
+struct Recorder(Mutex<Vec<Recorded>>);
+
+impl MetricsObserver for Recorder {
+    fn record(&self, call: &CallRecord<'_>) {
+        self.0.lock().unwrap().push(Recorded {
+            endpoint: call.endpoint.to_owned(),
+            method: call.method.clone(),
+            status: call.status,
+            retries: call.retries,
+            code: call.code(),
+        });
+    }
+}
+
+fn options(transport: &Arc<MemoryTransport>, recorder: &Arc<Recorder>) -> Option<TiktokOptions> {
+    Some(TiktokOptions {
+        transport: Some(transport.clone()),
+        metrics: Some(recorder.clone()),
+        retry: Some(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            jitter: false,
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn one_record_per_api_call() {
+    let transport = Arc::new(MemoryTransport::new());
+    let recorder = Arc::new(Recorder::default());
+    let options = options(&transport, &recorder);
+    transport.push_json(StatusCode::BAD_GATEWAY, "{}");
+    transport.push_json(StatusCode::OK, r#"{"error":{"code":"ok"}}"#);
+    transport.push_json(
+        StatusCode::UNAUTHORIZED,
+        r#"{"error":{"code":"access_token_invalid","message":"","log_id":"l"}}"#,
+    );
+    let url = "https://open.tiktokapis.com/v2/user/info/?fields=open_id";
+
+    execute_api::<serde_json::Value>(HttpRequest::get(url), &options)
+        .await
+        .unwrap();
+    execute_api::<serde_json::Value>(HttpRequest::get(url), &options)
+        .await
+        .unwrap_err();
+
+    let records = recorder.0.lock().unwrap();
+    assert_eq!(
+        *records,
+        vec![
+            Recorded {
+                endpoint: "/v2/user/info/".to_owned(),
+                method: Method::GET,
+                status: Some(StatusCode::OK),
+                retries: 1,
+                code: "ok".to_owned(),
+            },
+            Recorded {
+                endpoint: "/v2/user/info/".to_owned(),
+                method: Method::GET,
+                status: Some(StatusCode::UNAUTHORIZED),
+                retries: 0,
+                code: "access_token_invalid".to_owned(),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn one_record_per_oauth_call() {
+    let transport = Arc::new(MemoryTransport::new());
+    let recorder = Arc::new(Recorder::default());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"error":"invalid_grant","error_description":"","log_id":"a"}"#,
+    );
+    let oauth = TiktokOauth::new_with_options(
+        "key",
+        "secret",
+        "http://localhost",
+        vec![],
+        options(&transport, &recorder),
+    );
+
+    oauth.refresh("rt").await.unwrap_err();
+
+    let records = recorder.0.lock().unwrap();
+    assert_eq!(
+        *records,
+        vec![Recorded {
+            endpoint: "/v2/oauth/token/".to_owned(),
+            method: Method::POST,
+            status: Some(StatusCode::OK),
+            retries: 0,
+            code: "invalid_grant".to_owned(),
+        }]
+    );
+}
@@ -0,0 +1,115 @@
+use http::{header::CONTENT_TYPE, HeaderValue, StatusCode};
+use std::sync::Arc;
+use tiktokapi_v2::{
+    error::{Error, OAuthErrorCode},
+    oauth::{RevokeResult, TiktokOauth},
+    options::TiktokOptions,
+    transport::{HttpResponse, MemoryTransport},
+};
+
+fn oauth(transport: &Arc<MemoryTransport>) -> TiktokOauth {
+    let options = TiktokOptions {
+        transport: Some(transport.clone()),
+        ..Default::default()
+    };
+    TiktokOauth::new_with_options("key", "secret", "http://localhost", vec![], Some(options))
+}
+
+#[tokio::test]
+async fn html_bad_gateway_is_other() {
+    let transport = Arc::new(MemoryTransport::new());
+    let mut response = HttpResponse::new(
+        StatusCode::BAD_GATEWAY,
+        "<html><body>502 Bad Gateway</body></html>",
+    );
+    response
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+    transport.push_response(response);
+
+    let res = oauth(&transport).token("code").await;
+    match res {
+        Err(Error::Other(text, status, _)) => {
+            assert_eq!(status, StatusCode::BAD_GATEWAY);
+            assert!(text.contains("502 Bad Gateway"));
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[tokio::test]
+async fn flat_error_with_ok_status_is_oauth_error() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"error":"invalid_grant","error_description":"Refresh token is invalid or expired.","log_id":"abc"}"#,
+    );
+
+    let err = oauth(&transport).refresh("rt").await.unwrap_err();
+    match &err {
+        Error::OAuth(oauth_error, status) => {
+            assert_eq!(oauth_error.error, OAuthErrorCode::InvalidGrant);
+            assert_eq!(*status, StatusCode::OK);
+        }
+        err => panic!("unexpected {:?}", err),
+    }
+    assert!(err.requires_reauthorization());
+    assert_eq!(err.log_id(), Some("abc"));
+}
+
+#[tokio::test]
+async fn revoke_of_invalid_token_is_already_invalid() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"error":"invalid_grant","error_description":"Access token is invalid.","log_id":"a"}"#,
+    );
+    transport.push_json(
+        StatusCode::UNAUTHORIZED,
+        r#"{"error":{"code":"access_token_invalid","message":"","log_id":"b"}}"#,
+    );
+    transport.push_response(HttpResponse::new(StatusCode::OK, ""));
+
+    let oauth = oauth(&transport);
+    assert_eq!(
+        oauth.revoke("at").await.unwrap(),
+        RevokeResult::AlreadyInvalid
+    );
+    assert_eq!(
+        oauth.revoke("at").await.unwrap(),
+        RevokeResult::AlreadyInvalid
+    );
+    assert_eq!(oauth.revoke("at").await.unwrap(), RevokeResult::Revoked);
+}
+
+#[tokio::test]
+async fn malformed_token_body_does_not_leak_tokens() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"access_token":"secret-at","refresh_token":"secret-rt","expires_in":"soon"}"#,
+    );
+
+    let err = oauth(&transport).token("code").await.unwrap_err();
+    assert!(matches!(err, Error::Json(_)));
+    let text = format!("{} {:?}", err, err);
+    assert!(!text.contains("secret-at"));
+    assert!(!text.contains("secret-rt"));
+}
+
+#[tokio::test]
+async fn malformed_error_with_ok_status_does_not_keep_the_body() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"access_token":"secret-at","error":{"code":1}}"#,
+    );
+    transport.push_json(StatusCode::OK, "<html>secret-at</html>");
+
+    let oauth = oauth(&transport);
+    for _ in 0..2 {
+        let err = oauth.token("code").await.unwrap_err();
+        assert!(matches!(err, Error::Json(_)), "{:?}", err);
+        assert!(!format!("{} {:?}", err, err).contains("secret-at"));
+    }
+}
@@ -0,0 +1,95 @@
+use http::StatusCode;
+use std::{collections::HashSet, sync::Arc};
+use tiktokapi_v2::{
+    apis::post_v2_video_list::Body,
+    client::TiktokClient,
+    error::Error,
+    options::TiktokOptions,
+    responses::{
+        projection::UserView,
+        user::{User, UserField},
+        video::VideoField,
+    },
+    transport::MemoryTransport,
+};
+
+fn client(transport: &Arc<MemoryTransport>) -> TiktokClient<String> {
+    let options = TiktokOptions {
+        transport: Some(transport.clone()),
+        ..Default::default()
+    };
+    TiktokClient::new("token".to_owned(), Some(options))
+}
+
+#[test]
+fn accessors_check_requested_and_present_fields() {
+    let user = User {
+        open_id: Some("o1".to_owned()),
+        follower_count: Some(3_000_000_000),
+        ..Default::default()
+    };
+    let view = UserView::new(
+        user,
+        HashSet::from([
+            UserField::OpenId,
+            UserField::DisplayName,
+            UserField::FollowerCount,
+        ]),
+    );
+
+    assert_eq!(view.open_id().unwrap(), "o1");
+    assert_eq!(view.follower_count().unwrap(), 3_000_000_000);
+    assert!(matches!(
+        view.display_name(),
+        Err(Error::MissingField(field)) if field == "display_name"
+    ));
+    assert!(matches!(
+        view.bio_description(),
+        Err(Error::FieldNotRequested(field)) if field == "bio_description"
+    ));
+}
+
+#[tokio::test]
+async fn client_views_keep_the_requested_fields() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"user":{"open_id":"o1","display_name":"name"}},"error":{"code":"ok"}}"#,
+    );
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"1","title":"t"}],"cursor":100,"has_more":true},"error":{"code":"ok"}}"#,
+    );
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"videos":[{"id":"2"}]},"error":{"code":"ok"}}"#,
+    );
+    let client = client(&transport);
+
+    let user = client
+        .user_info_view(HashSet::from([UserField::OpenId, UserField::DisplayName]))
+        .await
+        .unwrap();
+    assert_eq!(user.display_name().unwrap(), "name");
+    assert!(matches!(user.username(), Err(Error::FieldNotRequested(_))));
+
+    let fields = HashSet::from([VideoField::Id, VideoField::Title]);
+    let page = client
+        .video_list_view(fields.clone(), Body::default())
+        .await
+        .unwrap();
+    assert_eq!(page.cursor, Some(100));
+    assert!(page.has_more);
+    assert_eq!(page.videos[0].title().unwrap(), "t");
+    assert!(matches!(
+        page.videos[0].view_count(),
+        Err(Error::FieldNotRequested(_))
+    ));
+
+    let videos = client
+        .video_query_view(fields, vec!["2".to_owned()])
+        .await
+        .unwrap();
+    assert_eq!(videos[0].id().unwrap(), "2");
+    assert!(matches!(videos[0].title(), Err(Error::MissingField(_))));
+}
@@ -0,0 +1,94 @@
+use http::StatusCode;
+use std::sync::Arc;
+use tiktokapi_v2::{
+    apis::execute_api,
+    error::Error,
+    options::TiktokOptions,
+    quota::{MemoryQuotaStore, QuotaFuture, QuotaMode, QuotaStore, QuotaUsage, ResearchQuota},
+    transport::{HttpRequest, MemoryTransport},
+};
+
+const URL: &str = "https://open.tiktokapis.com/v2/research/video/query/";
+const VIDEOS: &str = r#"{"data":{"videos":[{"id":"1"},{"id":"2"}]},"error":{"code":"ok","message":"","log_id":"a"}}"#;
+
+fn options(transport: &Arc<MemoryTransport>, quota: ResearchQuota) -> Option<TiktokOptions> {
+    Some(TiktokOptions {
+        transport: Some(transport.clone()),
+        research_quota: Some(quota),
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn enforce_reserves_before_sending() {
+    let transport = Arc::new(MemoryTransport::new());
+    let quota = ResearchQuota::new("app", Arc::new(MemoryQuotaStore::new()))
+        .limit(QuotaUsage::new(2, 100))
+        .mode(QuotaMode::Enforce);
+    let options = options(&transport, quota.clone());
+    transport.push_json(StatusCode::OK, VIDEOS);
+    transport.push_json(StatusCode::OK, VIDEOS);
+
+    for _ in 0..2 {
+        execute_api::<serde_json::Value>(HttpRequest::post(URL), &options)
+            .await
+            .unwrap();
+    }
+    let res = execute_api::<serde_json::Value>(HttpRequest::post(URL), &options).await;
+    assert!(matches!(res, Err(Error::QuotaExhausted(_))));
+    assert_eq!(transport.requests().len(), 2);
+    assert_eq!(quota.usage().await.unwrap(), QuotaUsage::new(2, 4));
+}
+
+#[tokio::test]
+async fn track_counts_requests_and_records() {
+    let transport = Arc::new(MemoryTransport::new());
+    let quota = ResearchQuota::new("app", Arc::new(MemoryQuotaStore::new()));
+    let options = options(&transport, quota.clone());
+    transport.push_json(StatusCode::OK, VIDEOS);
+
+    execute_api::<serde_json::Value>(HttpRequest::post(URL), &options)
+        .await
+        .unwrap();
+    assert_eq!(quota.usage().await.unwrap(), QuotaUsage::new(1, 2));
+}
+
+struct FailingStore;
+
+impl QuotaStore for FailingStore {
+    fn load<'a>(&'a self, _app: &'a str, _day: u64) -> QuotaFuture<'a, QuotaUsage> {
+        Box::pin(async { Ok(QuotaUsage::default()) })
+    }
+
+    fn add<'a>(
+        &'a self,
+        _app: &'a str,
+        _day: u64,
+        _usage: QuotaUsage,
+    ) -> QuotaFuture<'a, QuotaUsage> {
+        Box::pin(async { Err(Error::Transport("store down".to_owned())) })
+    }
+
+    fn add_within<'a>(
+        &'a self,
+        _app: &'a str,
+        _day: u64,
+        _usage: QuotaUsage,
+        _limit: QuotaUsage,
+    ) -> QuotaFuture<'a, QuotaUsage> {
+        Box::pin(async { Err(Error::Transport("store down".to_owned())) })
+    }
+}
+
+#[tokio::test]
+async fn record_failure_keeps_the_response() {
+    let transport = Arc::new(MemoryTransport::new());
+    let quota = ResearchQuota::new("app", Arc::new(FailingStore));
+    let options = options(&transport, quota);
+    transport.push_json(StatusCode::OK, VIDEOS);
+
+    let res = execute_api::<serde_json::Value>(HttpRequest::post(URL), &options)
+        .await
+        .unwrap();
+    assert_eq!(res.response["data"]["videos"][1]["id"], "2");
+}
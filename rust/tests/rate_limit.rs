@@ -0,0 +1,55 @@
+use std::time::Duration;
+use tiktokapi_v2::rate_limit::{Quota, RateLimiter};
+
+#[test]
+fn research_paths_do_not_match_display_quotas() {
+    let limiter = RateLimiter::tiktok_default();
+    assert_eq!(
+        limiter.quota("/v2/research/video/query/"),
+        Some(Quota::per_day(1000))
+    );
+    assert_eq!(
+        limiter.quota("/v2/research/user/info/"),
+        Some(Quota::per_day(1000))
+    );
+    assert_eq!(
+        limiter.quota("/v2/video/query/"),
+        Some(Quota::per_minute(600))
+    );
+    assert_eq!(
+        limiter.quota("/v2/user/info/"),
+        Some(Quota::per_minute(600))
+    );
+    assert_eq!(limiter.quota("/v2/oauth/token/"), None);
+}
+
+#[test]
+fn buckets_are_per_endpoint_and_token() {
+    let limiter = RateLimiter::new()
+        .with_quota("/video/query/", Quota::new(1, Duration::from_secs(60)))
+        .with_quota(
+            "/research/video/query/",
+            Quota::new(1, Duration::from_secs(60)),
+        );
+    assert_eq!(limiter.try_acquire("/v2/video/query/", "a"), None);
+    assert!(limiter.try_acquire("/v2/video/query/", "a").is_some());
+    assert_eq!(limiter.try_acquire("/v2/video/query/", "b"), None);
+    assert_eq!(limiter.try_acquire("/v2/research/video/query/", "a"), None);
+}
+
+#[test]
+fn research_endpoints_share_one_app_bucket() {
+    let limiter = RateLimiter::new().with_shared_quota(
+        "research",
+        &["/research/video/query/", "/research/user/info/"],
+        Quota::new(2, Duration::from_secs(60)),
+    );
+    assert_eq!(limiter.try_acquire("/v2/research/video/query/", "a"), None);
+    assert_eq!(limiter.try_acquire("/v2/research/user/info/", "b"), None);
+    assert!(limiter
+        .try_acquire("/v2/research/video/query/", "c")
+        .is_some());
+    assert!(limiter
+        .try_acquire("/v2/research/user/info/", "a")
+        .is_some());
+}
@@ -0,0 +1,108 @@
+use http::{HeaderValue, StatusCode};
+use std::sync::Arc;
+use tiktokapi_v2::{
+    apis::{execute_api, post_v2_video_query::Response},
+    error::Error as ApiError,
+    options::TiktokOptions,
+    responses::{
+        error::{Code, Error},
+        video::Video,
+    },
+    transport::{HttpRequest, HttpResponse, MemoryTransport, LOG_ID},
+};
+
+#[test]
+fn unknown_code_round_trips() {
+    let json = r#"{"code":"some_new_code","message":"m","log_id":"l"}"#;
+    let err: Error = serde_json::from_str(json).unwrap();
+    assert_eq!(err.code, Some(Code::Unknown("some_new_code".to_owned())));
+    assert_eq!(serde_json::to_value(&err).unwrap()["code"], "some_new_code");
+
+    let err: Error = serde_json::from_str(r#"{"code":"spam_risk_too_many_posts"}"#).unwrap();
+    assert_eq!(err.code, Some(Code::SpamRiskTooManyPosts));
+    assert_eq!(
+        Code::SpamRiskTooManyPosts.to_string(),
+        "spam_risk_too_many_posts"
+    );
+}
+
+#[test]
+fn counters_accept_numbers_and_quoted_numbers() {
+    let video: Video = serde_json::from_str(
+        r#"{"id":"1","view_count":"3000000000","like_count":2500000000,"share_count":null}"#,
+    )
+    .unwrap();
+    assert_eq!(video.view_count, Some(3_000_000_000));
+    assert_eq!(video.like_count, Some(2_500_000_000));
+    assert_eq!(video.share_count, None);
+    assert_eq!(video.comment_count, None);
+
+    assert!(serde_json::from_str::<Video>(r#"{"view_count":"many"}"#).is_err());
+}
+
+async fn execute(body: &str) -> Result<tiktokapi_v2::apis::ApiResponse<Response>, ApiError> {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(StatusCode::OK, body);
+    let options = Some(TiktokOptions {
+        transport: Some(transport),
+        ..Default::default()
+    });
+    execute_api(
+        HttpRequest::get("https://example.com/v2/video/query/"),
+        &options,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn execute_parses_the_body() {
+    let res = execute(
+        r#"{"data":{"videos":[{"id":"1","view_count":"3000000000"}]},"error":{"code":"ok","log_id":"l1"}}"#,
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.log_id.as_deref(), Some("l1"));
+    let videos = res.response.data.unwrap().videos.unwrap();
+    assert_eq!(videos[0].view_count, Some(3_000_000_000));
+
+    let err = execute(r#"{"error":{"code":"scope_not_authorized","log_id":"l2"}}"#)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ApiError::Api(err, StatusCode::OK) if err.code == Some(Code::ScopeNotAuthorized))
+    );
+
+    assert!(matches!(execute("<html>").await, Err(ApiError::Json(_))));
+}
+
+#[tokio::test]
+async fn failures_carry_the_log_id_header() {
+    let transport = Arc::new(MemoryTransport::new());
+    for (status, body) in [
+        (StatusCode::BAD_GATEWAY, "<html>502 Bad Gateway</html>"),
+        (
+            StatusCode::UNAUTHORIZED,
+            r#"{"error":{"code":"access_token_invalid","message":""}}"#,
+        ),
+    ] {
+        let mut response = HttpResponse::new(status, body);
+        response
+            .headers
+            .insert(LOG_ID, HeaderValue::from_static("header-log-id"));
+        transport.push_response(response);
+    }
+    let options = Some(TiktokOptions {
+        transport: Some(transport),
+        ..Default::default()
+    });
+
+    for _ in 0..2 {
+        let err = execute_api::<Response>(
+            HttpRequest::get("https://example.com/v2/video/query/"),
+            &options,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.log_id(), Some("header-log-id"), "{:?}", err);
+    }
+}
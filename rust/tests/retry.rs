@@ -0,0 +1,135 @@
+use http::StatusCode;
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tiktokapi_v2::{
+    apis::execute_api,
+    error::Error,
+    options::TiktokOptions,
+    rate_limit::{Quota, RateLimiter},
+    retry::RetryPolicy,
+    transport::{HttpRequest, MemoryTransport, Transport, TransportFuture},
+};
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        initial_backoff: Duration::from_millis(1),
+        jitter: false,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn retry_after_respects_attempts_and_deadline() {
+    let err = Error::Other("".to_owned(), StatusCode::BAD_GATEWAY, None);
+    let policy = policy();
+    assert_eq!(
+        policy.retry_after(1, Duration::ZERO, &err),
+        Some(Duration::from_millis(1))
+    );
+    assert_eq!(policy.retry_after(3, Duration::ZERO, &err), None);
+    let bad_request = Error::Other("".to_owned(), StatusCode::BAD_REQUEST, None);
+    assert_eq!(policy.retry_after(1, Duration::ZERO, &bad_request), None);
+
+    let policy = RetryPolicy {
+        deadline: Some(Duration::from_secs(1)),
+        ..policy
+    };
+    assert_eq!(policy.retry_after(1, Duration::from_secs(1), &err), None);
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(StatusCode::BAD_GATEWAY, "{}");
+    transport.push_json(StatusCode::OK, r#"{"error":{"code":"ok"}}"#);
+    let options = Some(TiktokOptions {
+        transport: Some(transport.clone()),
+        retry: Some(policy()),
+        ..Default::default()
+    });
+
+    execute_api::<serde_json::Value>(
+        HttpRequest::get("https://example.com/v2/user/info/"),
+        &options,
+    )
+    .await
+    .unwrap();
+    assert_eq!(transport.requests().len(), 2);
+}
+
+// Fails the first request the way a custom HTTP stack reports a timeout.
+#[derive(Debug)]
+struct TimeoutTransport {
+    calls: AtomicU32,
+    inner: MemoryTransport,
+}
+
+impl Transport for TimeoutTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        if self.calls.fetch_add(1, Ordering::Relaxed) == 0 {
+            return Box::pin(async { Err(Error::Timeout("read timed out".into())) });
+        }
+        self.inner.send(request)
+    }
+}
+
+#[tokio::test]
+async fn custom_transport_timeouts_are_retried() {
+    let transport = Arc::new(TimeoutTransport {
+        calls: AtomicU32::new(0),
+        inner: MemoryTransport::new(),
+    });
+    transport
+        .inner
+        .push_json(StatusCode::OK, r#"{"error":{"code":"ok"}}"#);
+    let options = Some(TiktokOptions {
+        transport: Some(transport.clone()),
+        retry: Some(policy()),
+        ..Default::default()
+    });
+
+    execute_api::<serde_json::Value>(
+        HttpRequest::get("https://example.com/v2/user/info/"),
+        &options,
+    )
+    .await
+    .unwrap();
+    assert_eq!(transport.calls.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn deadline_caps_request_timeout_and_limiter_wait() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(StatusCode::OK, r#"{"error":{"code":"ok"}}"#);
+    let options = Some(TiktokOptions {
+        transport: Some(transport.clone()),
+        retry: Some(RetryPolicy {
+            deadline: Some(Duration::from_secs(1)),
+            ..policy()
+        }),
+        rate_limiter: Some(Arc::new(
+            RateLimiter::new().with_quota("/user/info/", Quota::per_minute(1)),
+        )),
+        ..Default::default()
+    });
+    let request =
+        HttpRequest::get("https://example.com/v2/user/info/").timeout(Duration::from_secs(30));
+
+    execute_api::<serde_json::Value>(request.clone(), &options)
+        .await
+        .unwrap();
+    let timeout = transport.requests()[0].timeout.unwrap();
+    assert!(timeout <= Duration::from_secs(1));
+
+    // The next token is a minute away, past the deadline.
+    let err = execute_api::<serde_json::Value>(request, &options)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Timeout(_)));
+    assert_eq!(transport.requests().len(), 1);
+}
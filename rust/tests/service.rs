@@ -0,0 +1,62 @@
+#![cfg(feature = "tower")]
+
+use http::{header::AUTHORIZATION, StatusCode};
+use std::sync::Arc;
+use tiktokapi_v2::{
+    apis::get_v2_user_info::{Api, Response},
+    error::Error,
+    options::TiktokOptions,
+    responses::{error::Code, user::UserField},
+    service::{call, ApiService},
+    transport::MemoryTransport,
+};
+
+fn options(transport: &Arc<MemoryTransport>) -> Option<TiktokOptions> {
+    Some(TiktokOptions {
+        transport: Some(transport.clone()),
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn service_sends_built_requests() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::OK,
+        r#"{"data":{"user":{"open_id":"o1","display_name":"name"}},"error":{"code":"ok","log_id":"l1"}}"#,
+    );
+    let options = options(&transport);
+    let mut service = ApiService::<Response>::new(options.clone());
+
+    let request = Api::new(UserField::all(), options).build("token").unwrap();
+    let res = call(&mut service, request).await.unwrap();
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.log_id.as_deref(), Some("l1"));
+    let user = res.into_inner().data.unwrap().user.unwrap();
+    assert_eq!(user.open_id.as_deref(), Some("o1"));
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].url.contains("/user/info/"));
+    assert_eq!(requests[0].headers[AUTHORIZATION], "Bearer token");
+}
+
+#[tokio::test]
+async fn service_returns_api_errors() {
+    let transport = Arc::new(MemoryTransport::new());
+    transport.push_json(
+        StatusCode::UNAUTHORIZED,
+        r#"{"error":{"code":"access_token_invalid","message":"","log_id":"l2"}}"#,
+    );
+    let options = options(&transport);
+    let mut service = ApiService::<Response>::new(options.clone());
+
+    let request = Api::new(UserField::all(), options).build("token").unwrap();
+    match call(&mut service, request).await {
+        Err(Error::Api(err, status)) => {
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert_eq!(err.code, Some(Code::AccessTokenInvalid));
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+}
@@ -0,0 +1,78 @@
+use http::StatusCode;
+use std::sync::Arc;
+use tiktokapi_v2::{
+    client::CredentialSource,
+    oauth::{TiktokOauth, TokenResult},
+    options::TiktokOptions,
+    responses::video::VideoField,
+    session::{AccountStatus, SessionManager},
+    token_store::MemoryTokenStore,
+    transport::MemoryTransport,
+};
+
+const INVALID: &str = r#"{"error":{"code":"access_token_invalid","message":"","log_id":"a"}}"#;
+const OK: &str = r#"{"data":{"videos":[]},"error":{"code":"ok","message":"","log_id":"b"}}"#;
+
+fn token(access_token: &str, refresh_token: &str) -> String {
+    format!(
+        r#"{{"open_id":"o1","scope":"video.list","access_token":"{}","expires_in":86400,"refresh_token":"{}","refresh_expires_in":31536000,"token_type":"Bearer"}}"#,
+        access_token, refresh_token
+    )
+}
+
+fn manager(transport: &Arc<MemoryTransport>) -> SessionManager<MemoryTokenStore> {
+    let options = TiktokOptions {
+        transport: Some(transport.clone()),
+        ..Default::default()
+    };
+    let oauth = TiktokOauth::new_with_options(
+        "key",
+        "secret",
+        "http://localhost",
+        vec![],
+        Some(options.clone()),
+    );
+    SessionManager::new(oauth, MemoryTokenStore::new(), Some(options))
+}
+
+#[tokio::test]
+async fn stale_token_is_refreshed_once() {
+    let transport = Arc::new(MemoryTransport::new());
+    let manager = manager(&transport);
+    let first: TokenResult = serde_json::from_str(&token("at1", "rt1")).unwrap();
+    manager.add(&first).await.unwrap();
+    transport.push_json(StatusCode::OK, &token("at2", "rt2"));
+
+    let client = manager.for_account("o1");
+    let handle = client.credentials();
+    // Two calls rejected with the same token: only the first one refreshes.
+    let a = handle.refresh_access_token("at1").await.unwrap();
+    let b = handle.refresh_access_token("at1").await.unwrap();
+    assert_eq!(a.as_deref(), Some("at2"));
+    assert_eq!(b.as_deref(), Some("at2"));
+    assert_eq!(transport.requests().len(), 1);
+    assert_eq!(manager.status("o1"), Some(AccountStatus::Active));
+}
+
+#[tokio::test]
+async fn rejected_call_retries_with_refreshed_token() {
+    let transport = Arc::new(MemoryTransport::new());
+    let manager = manager(&transport);
+    let first: TokenResult = serde_json::from_str(&token("at1", "rt1")).unwrap();
+    manager.add(&first).await.unwrap();
+    transport.push_json(StatusCode::OK, INVALID);
+    transport.push_json(StatusCode::OK, &token("at2", "rt2"));
+    transport.push_json(StatusCode::OK, OK);
+
+    let client = manager.for_account("o1");
+    client
+        .video_query(VideoField::all(), vec!["1".to_owned()])
+        .await
+        .unwrap();
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(
+        requests[2].headers["authorization"].to_str().unwrap(),
+        "Bearer at2"
+    );
+}
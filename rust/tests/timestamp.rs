@@ -0,0 +1,59 @@
+use std::time::{Duration, UNIX_EPOCH};
+use tiktokapi_v2::responses::video::Video;
+
+fn video(create_time: i64) -> Video {
+    Video {
+        create_time: Some(create_time),
+        duration: Some(15),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn created_at_at_and_before_the_epoch() {
+    assert_eq!(video(0).created_at(), Some(UNIX_EPOCH));
+    assert_eq!(
+        video(-1).created_at(),
+        UNIX_EPOCH.checked_sub(Duration::from_secs(1))
+    );
+    assert_eq!(
+        video(1_700_000_000).created_at(),
+        Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+    );
+    assert_eq!(Video::default().created_at(), None);
+}
+
+#[test]
+fn length_is_in_seconds() {
+    assert_eq!(video(0).length(), Some(Duration::from_secs(15)));
+    let video = Video {
+        duration: Some(-1),
+        ..Default::default()
+    };
+    assert_eq!(video.length(), None);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn created_at_chrono() {
+    use chrono::{TimeZone, Utc};
+    assert_eq!(
+        video(0).created_at_chrono(),
+        Some(Utc.with_ymd_and_hms(1970, 1, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        video(-1).created_at_chrono(),
+        Some(Utc.with_ymd_and_hms(1969, 12, 31, 23, 59, 59).unwrap())
+    );
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn created_at_time() {
+    use time::OffsetDateTime;
+    assert_eq!(video(0).created_at_time(), Some(OffsetDateTime::UNIX_EPOCH));
+    assert_eq!(
+        video(-1).created_at_time(),
+        Some(OffsetDateTime::UNIX_EPOCH - time::Duration::seconds(1))
+    );
+}
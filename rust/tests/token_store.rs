@@ -0,0 +1,59 @@
+#![cfg(feature = "encryption")]
+
+use tiktokapi_v2::{
+    oauth::TokenResult,
+    token_store::{EncryptedTokenStore, MemoryTokenStore, TokenCipher, TokenStore},
+};
+
+fn token() -> TokenResult {
+    serde_json::from_str(
+        r#"{"open_id":"o1","scope":"user.info.basic","access_token":"at","expires_in":86400,"refresh_token":"rt","refresh_expires_in":31536000,"token_type":"Bearer"}"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn seal_and_open() {
+    let cipher = TokenCipher::new("k1", &[1u8; 32]).unwrap();
+    let sealed = cipher.seal("secret", "o1:access_token").unwrap();
+    assert!(sealed.starts_with("k1:"));
+    assert!(!sealed.contains("secret"));
+    assert_eq!(cipher.open(&sealed, "o1:access_token").unwrap(), "secret");
+    // The value is bound to the account and field.
+    assert!(cipher.open(&sealed, "o2:access_token").is_err());
+    assert!(cipher.open(&sealed, "o1:refresh_token").is_err());
+}
+
+#[test]
+fn rotate_keeps_old_values_readable() {
+    let mut cipher = TokenCipher::new("k1", &[1u8; 32]).unwrap();
+    let old = cipher.seal("secret", "aad").unwrap();
+    cipher.rotate("k2", &[2u8; 32]).unwrap();
+    let new = cipher.seal("secret", "aad").unwrap();
+    assert!(new.starts_with("k2:"));
+    assert_eq!(cipher.open(&old, "aad").unwrap(), "secret");
+    assert!(!cipher.is_current(&old));
+    assert!(cipher.remove_key("k2").is_err());
+    cipher.remove_key("k1").unwrap();
+    assert!(cipher.open(&old, "aad").is_err());
+}
+
+#[tokio::test]
+async fn live_store_rotation_reencrypts() {
+    let cipher = TokenCipher::new("k1", &[1u8; 32]).unwrap();
+    let store = EncryptedTokenStore::new(MemoryTokenStore::new(), cipher);
+    store.save(&token()).await.unwrap();
+    let sealed = store.inner().load("o1").await.unwrap().unwrap();
+    assert!(sealed.access_token.starts_with("k1:"));
+
+    store.rotate("k2", &[2u8; 32]).unwrap();
+    assert_eq!(store.reencrypt_all().await.unwrap(), 1);
+    assert_eq!(store.reencrypt_all().await.unwrap(), 0);
+    let sealed = store.inner().load("o1").await.unwrap().unwrap();
+    assert!(sealed.access_token.starts_with("k2:"));
+    assert!(sealed.refresh_token.starts_with("k2:"));
+
+    let token = store.load("o1").await.unwrap().unwrap();
+    assert_eq!(token.access_token, "at");
+    assert_eq!(token.refresh_token, "rt");
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "tracing")]
+
+use http::StatusCode;
+use std::sync::{Arc, Mutex};
+use tiktokapi_v2::{
+    apis::execute_api,
+    options::TiktokOptions,
+    transport::{HttpRequest, MemoryTransport, Transport, TransportFuture},
+};
+use tracing::{
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+// Records, for every event, the names of the spans entered at that moment.
+#[derive(Default)]
+struct Recorder {
+    names: Mutex<Vec<&'static str>>,
+    entered: Mutex<Vec<u64>>,
+    events: Arc<Mutex<Vec<Vec<&'static str>>>>,
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut names = self.names.lock().unwrap();
+        names.push(span.metadata().name());
+        Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {
+        let names = self.names.lock().unwrap();
+        let entered = self.entered.lock().unwrap();
+        self.events
+            .lock()
+            .unwrap()
+            .push(entered.iter().map(|id| names[*id as usize - 1]).collect());
+    }
+
+    fn enter(&self, span: &Id) {
+        self.entered.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, span: &Id) {
+        let mut entered = self.entered.lock().unwrap();
+        if let Some(index) = entered.iter().rposition(|it| *it == span.into_u64()) {
+            entered.remove(index);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EventTransport(MemoryTransport);
+
+impl Transport for EventTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        tracing::info!("sending");
+        self.0.send(request)
+    }
+}
+
+#[tokio::test]
+async fn transport_runs_inside_the_span() {
+    let recorder = Recorder::default();
+    let events = recorder.events.clone();
+    let _guard = tracing::subscriber::set_default(recorder);
+
+    let transport = MemoryTransport::new();
+    transport.push_json(StatusCode::OK, r#"{"error":{"code":"ok"}}"#);
+    let options = Some(TiktokOptions {
+        transport: Some(Arc::new(EventTransport(transport))),
+        ..Default::default()
+    });
+    execute_api::<serde_json::Value>(
+        HttpRequest::get("https://example.com/v2/user/info/"),
+        &options,
+    )
+    .await
+    .unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events[0], vec!["tiktok_api"]);
+}
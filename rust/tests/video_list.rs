@@ -0,0 +1,39 @@
+use tiktokapi_v2::{
+    apis::post_v2_video_list::{Api, Body, Response},
+    error::Error,
+    responses::video::VideoField,
+};
+
+fn build(max_count: Option<i32>) -> Option<Error> {
+    let body = Body {
+        cursor: None,
+        max_count,
+    };
+    Api::new(VideoField::all(), body, None).build("token").err()
+}
+
+#[test]
+fn max_count_must_be_between_1_and_20() {
+    assert!(matches!(build(Some(0)), Some(Error::Validation(_))));
+    assert!(matches!(build(Some(21)), Some(Error::Validation(_))));
+    assert!(build(Some(1)).is_none());
+    assert!(build(Some(20)).is_none());
+    assert!(build(None).is_none());
+}
+
+#[test]
+fn paging_is_read_from_data_then_top_level() {
+    let res: Response =
+        serde_json::from_str(r#"{"data":{"cursor":100,"has_more":true},"cursor":1}"#).unwrap();
+    assert_eq!(res.next_cursor(), Some(100));
+    assert!(res.has_more());
+
+    let res: Response =
+        serde_json::from_str(r#"{"data":{"videos":[]},"cursor":200,"has_more":true}"#).unwrap();
+    assert_eq!(res.next_cursor(), Some(200));
+    assert!(res.has_more());
+
+    let res: Response = serde_json::from_str(r#"{"data":{}}"#).unwrap();
+    assert_eq!(res.next_cursor(), None);
+    assert!(!res.has_more());
+}